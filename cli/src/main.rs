@@ -0,0 +1,287 @@
+//! 查看及运行 Paddle 模型的命令行工具
+//!
+//! - `info`：打印动态库版本、模型的输入输出及生效的配置
+//! - `run`：使用`.npy`、`.npz`文件或常量作为输入运行模型，并将输出保存为`.npy`文件，启用 Profile 时打印各运算的耗时
+//! - `passes`：打印应用配置后的 IR 优化 pass 列表
+//! - `bench`：测试预测的延迟、吞吐量及内存占用
+
+mod options;
+
+use clap::{Args, Parser, Subcommand};
+use options::ConfigArgs;
+use paddle_inference::common::DataType;
+use paddle_inference::{
+    benchmark, npy, BenchInput, BenchOptions, Element, HostTensor, ProfileReport, ProfileSortKey,
+    Tensor,
+};
+use std::error::Error;
+use std::path::PathBuf;
+
+/// 查看及运行 Paddle 模型
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// 打印动态库版本、模型的输入输出及生效的配置
+    Info(ConfigArgs),
+    /// 运行模型，并将所有输出保存为`.npy`文件
+    Run(RunArgs),
+    /// 打印 IR 优化的 pass 列表
+    Passes(ConfigArgs),
+    /// 测试预测的延迟、吞吐量及内存占用
+    Bench(BenchArgs),
+}
+
+#[derive(Debug, Args)]
+struct RunArgs {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// 从`.npy`文件读取输入，格式为`名称=路径`，可以重复设置
+    #[arg(short, long, value_name = "NAME=PATH", value_parser = parse_input, help_heading = "输入输出")]
+    input: Vec<(String, PathBuf)>,
+    /// 从`.npz`文件读取输入，文件中的每个数组对应同名的输入
+    #[arg(long, value_name = "PATH", help_heading = "输入输出")]
+    npz: Option<PathBuf>,
+    /// 使用常量填充输入，格式为`名称=shape[:值]`，shape 以`,`分隔，值默认为`0`，数据类型与模型输入相同
+    #[arg(long, value_name = "NAME=SHAPE[:VALUE]", value_parser = parse_fill, help_heading = "输入输出")]
+    fill: Vec<(String, Vec<usize>, f64)>,
+    /// 保存输出的目录，每个输出保存为`<名称>.npy`，名称中的`/`及`\`替换为`_`
+    #[arg(short, long, default_value = ".", help_heading = "输入输出")]
+    output_dir: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct BenchArgs {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// 输入的 shape 及数据类型，格式为`名称=shape[:数据类型]`，数据类型可选`float32`、`int64`、`int32`、`uint8`、
+    /// `int8`，默认使用模型中声明的类型
+    #[arg(short, long, value_name = "NAME=SHAPE[:DTYPE]", value_parser = parse_shape, help_heading = "性能测试")]
+    shape: Vec<BenchInput>,
+    /// 每个预测器的预热次数
+    #[arg(long, default_value_t = 10, help_heading = "性能测试")]
+    warmup: usize,
+    /// 每个预测器测量的次数
+    #[arg(short = 'n', long, default_value_t = 100, help_heading = "性能测试")]
+    iterations: usize,
+    /// 并行运行的预测器数量
+    #[arg(short = 'j', long, default_value_t = 1, help_heading = "性能测试")]
+    predictors: usize,
+    /// 以 JSON 格式输出结果
+    #[arg(long, help_heading = "性能测试")]
+    json: bool,
+}
+
+fn parse_input(s: &str) -> Result<(String, PathBuf), String> {
+    let (name, path) = s
+        .split_once('=')
+        .ok_or_else(|| format!("`{}`的格式应为`名称=路径`", s))?;
+    Ok((name.to_string(), path.into()))
+}
+
+fn parse_fill(s: &str) -> Result<(String, Vec<usize>, f64), String> {
+    let (name, rest) = s
+        .split_once('=')
+        .ok_or_else(|| format!("`{}`的格式应为`名称=shape[:值]`", s))?;
+    let (shape, value) = rest.split_once(':').unwrap_or((rest, "0"));
+    let shape = shape
+        .split(',')
+        .filter(|d| !d.is_empty())
+        .map(|d| d.trim().parse().map_err(|_| format!("无效的维度`{}`", d)))
+        .collect::<Result<_, _>>()?;
+    let value = value.parse().map_err(|_| format!("无效的值`{}`", value))?;
+    Ok((name.to_string(), shape, value))
+}
+
+fn parse_shape(s: &str) -> Result<BenchInput, String> {
+    let (name, rest) = s
+        .split_once('=')
+        .ok_or_else(|| format!("`{}`的格式应为`名称=shape[:数据类型]`", s))?;
+    let (shape, data_type) = match rest.split_once(':') {
+        Some((shape, data_type)) => (shape, Some(data_type)),
+        None => (rest, None),
+    };
+    let shape = shape
+        .split(',')
+        .filter(|d| !d.is_empty())
+        .map(|d| d.trim().parse().map_err(|_| format!("无效的维度`{}`", d)))
+        .collect::<Result<_, _>>()?;
+    let mut input = BenchInput::new(name, shape);
+    if let Some(data_type) = data_type {
+        input = input.data_type(match data_type {
+            "float32" => DataType::Float32,
+            "int64" => DataType::Int64,
+            "int32" => DataType::Int32,
+            "uint8" => DataType::Uint8,
+            "int8" => DataType::Int8,
+            _ => return Err(format!("不支持的数据类型`{}`", data_type)),
+        });
+    }
+    Ok(input)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    match Cli::parse().command {
+        Command::Info(args) => info(&args),
+        Command::Run(args) => run(&args),
+        Command::Passes(args) => {
+            for pass in args.config()?.all_passes()? {
+                println!("{}", pass);
+            }
+            Ok(())
+        }
+        Command::Bench(args) => bench(&args),
+    }
+}
+
+fn info(args: &ConfigArgs) -> Result<(), Box<dyn Error>> {
+    let mut predictor = args.config()?.build()?;
+    let runtime = predictor.runtime();
+    println!("动态库: {}", runtime.path().display());
+    match &runtime.capabilities().version_info {
+        Some(info) => {
+            println!("版本信息:");
+            info.lines().for_each(|l| println!("  {}", l));
+        }
+        None => println!("版本信息: 未知"),
+    }
+
+    // C API 不提供模型中声明的 shape，只能打印预测器当前的 shape
+    println!("输入(shape 为当前运行时的 shape，设置输入前可能为空):");
+    for name in Vec::<String>::from(predictor.input_names()) {
+        print_tensor(&predictor.input(&name)?);
+    }
+    println!("输出:");
+    for name in Vec::<String>::from(predictor.output_names()) {
+        print_tensor(&predictor.output(&name)?);
+    }
+
+    match &predictor.config_report().summary {
+        Some(summary) => {
+            println!("配置:");
+            summary.lines().for_each(|l| println!("  {}", l));
+        }
+        None => println!("配置: 动态库不支持`PD_ConfigSummary`"),
+    }
+    Ok(())
+}
+
+/// 打印 Tensor 的名称、数据类型及当前运行时的 shape
+fn print_tensor(tensor: &Tensor) {
+    println!(
+        "  {}: {:?} 当前 shape {:?}",
+        tensor.name(),
+        tensor.data_type(),
+        tensor.shape()
+    );
+}
+
+fn run(args: &RunArgs) -> Result<(), Box<dyn Error>> {
+    let config = args.config.config()?;
+    let profile = config.profile;
+    let mut predictor = config.build()?;
+
+    let mut inputs = match &args.npz {
+        Some(path) => npy::load_npz(path)?,
+        None => vec![],
+    };
+    for (name, path) in &args.input {
+        inputs.push((name.clone(), npy::load(path)?));
+    }
+    for (name, shape, value) in &args.fill {
+        let data_type = predictor.input(name)?.data_type();
+        inputs.push((name.clone(), fill(data_type, shape.clone(), *value)?));
+    }
+    for name in Vec::<String>::from(predictor.input_names()) {
+        if !inputs.iter().any(|(n, _)| *n == name) {
+            return Err(
+                format!("缺少输入`{}`，请通过`--input`、`--npz`或`--fill`设置", name).into(),
+            );
+        }
+    }
+
+    let outputs = predictor.run_host(&inputs)?;
+    std::fs::create_dir_all(&args.output_dir)?;
+    for (name, tensor) in outputs {
+        let path = args
+            .output_dir
+            .join(format!("{}.npy", name.replace(['/', '\\'], "_")));
+        npy::save(&path, &tensor)?;
+        println!(
+            "{}: {:?} {:?} -> {}",
+            name,
+            tensor.data_type(),
+            tensor.shape(),
+            path.display()
+        );
+    }
+
+    if profile {
+        #[cfg(unix)]
+        {
+            let ((), mut report) = ProfileReport::capture(|| drop(predictor))?;
+            report.sort_by(ProfileSortKey::Total);
+            println!("{}", report);
+        }
+    }
+    Ok(())
+}
+
+fn bench(args: &BenchArgs) -> Result<(), Box<dyn Error>> {
+    let options = BenchOptions {
+        inputs: args.shape.clone(),
+        warmup: args.warmup,
+        iterations: args.iterations,
+        threads: args.predictors,
+    };
+    let report = benchmark(args.config.config()?, &options)?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{}", report);
+    }
+    Ok(())
+}
+
+/// 创建所有元素均为`value`的 Tensor，shape 之积溢出时返回错误
+fn fill(data_type: DataType, shape: Vec<usize>, value: f64) -> Result<HostTensor, Box<dyn Error>> {
+    fn filled<T: Element>(shape: Vec<usize>, value: T) -> paddle_inference::Result<HostTensor> {
+        let mut tensor = HostTensor::zeros::<T>(shape)?;
+        tensor.as_mut_slice::<T>().unwrap().fill(value);
+        Ok(tensor)
+    }
+
+    let tensor = match data_type {
+        DataType::Float32 => filled(shape, value as f32),
+        DataType::Int64 => filled(shape, value as i64),
+        DataType::Int32 => filled(shape, value as i32),
+        DataType::Uint8 => filled(shape, value as u8),
+        DataType::Int8 => filled(shape, value as i8),
+        DataType::Unknown => return Err("输入的数据类型未知".into()),
+    };
+    Ok(tensor?)
+}
+
+#[test]
+fn test_cli() {
+    use clap::CommandFactory;
+    Cli::command().debug_assert();
+    assert_eq!(
+        parse_fill("x=1,3:0.5").unwrap(),
+        ("x".to_string(), vec![1, 3], 0.5)
+    );
+    assert!(parse_fill("x").is_err());
+    let input = parse_shape("x=1,3:int64").unwrap();
+    assert_eq!(input.shape, [1, 3]);
+    assert_eq!(input.data_type, Some(DataType::Int64));
+    assert!(parse_shape("x=1:float64").is_err());
+
+    let tensor = fill(DataType::Int32, vec![2, 2], 3.0).unwrap();
+    assert_eq!(tensor.as_slice::<i32>().unwrap(), [3; 4]);
+    assert!(fill(DataType::Float32, vec![usize::MAX, 2], 1.0).is_err());
+}
@@ -0,0 +1,338 @@
+//! 创建预测器的命令行参数
+
+use clap::Args;
+use paddle_inference::common::PrecisionType;
+use paddle_inference::config::lite_engine::LiteEngine;
+use paddle_inference::config::model::Model;
+use paddle_inference::config::setting::{
+    Cpu, Gpu, Mkldnn, ONNXRuntime, TensorRT, TunedDynamicShape, Xpu,
+};
+use paddle_inference::config::Config;
+use paddle_inference::Runtime;
+use std::path::PathBuf;
+
+/// 预测器配置
+///
+/// 可以通过`--config`读取 TOML 格式的[`Config`]，命令行参数会覆盖配置文件中的对应项。
+/// `--gpu`、`--trt`、`--xpu`、`--lite`及`--onnx-runtime`会使用命令行参数替换配置文件中的整个对应设置
+#[derive(Debug, Args)]
+pub struct ConfigArgs {
+    /// TOML 格式的配置文件，内容为`paddle_inference::config::Config`
+    #[arg(short, long, help_heading = "模型")]
+    config: Option<PathBuf>,
+    /// 非Combined 模型所在目录
+    #[arg(long, conflicts_with = "model", help_heading = "模型")]
+    model_dir: Option<String>,
+    /// Combined 模型文件路径
+    #[arg(short, long, requires = "params", help_heading = "模型")]
+    model: Option<String>,
+    /// Combined 模型参数文件路径
+    #[arg(short, long, requires = "model", help_heading = "模型")]
+    params: Option<String>,
+    /// `paddle_inference_c`动态库路径，不设置时使用默认动态库
+    #[arg(long, help_heading = "模型")]
+    library: Option<PathBuf>,
+
+    /// CPU Blas 库计算线程数
+    #[arg(long, help_heading = "CPU")]
+    threads: Option<i32>,
+    /// 启用 MKLDNN
+    #[arg(long, help_heading = "CPU")]
+    mkldnn: bool,
+    /// MKLDNN 针对不同输入 shape 的 cache 容量大小
+    #[arg(long, requires = "mkldnn", help_heading = "CPU")]
+    mkldnn_cache_size: Option<i32>,
+    /// 使用 MKLDNN 加速的 OP 列表，以`,`分隔
+    #[arg(long, value_delimiter = ',', requires = "mkldnn", help_heading = "CPU")]
+    mkldnn_op: Option<Vec<String>>,
+    /// 启用 MKLDNN BFLOAT16 并指定使用 BFLOAT16 加速的 OP 列表，以`,`分隔
+    #[arg(long, value_delimiter = ',', requires = "mkldnn", help_heading = "CPU")]
+    mkldnn_bf16_op: Option<Vec<String>>,
+
+    /// 使用 GPU 预测，值为设备id
+    #[arg(long, value_name = "DEVICE_ID", help_heading = "GPU")]
+    gpu: Option<i32>,
+    /// 初始化分配的 GPU 显存，以MB为单位
+    #[arg(long, default_value_t = 100, requires = "gpu", help_heading = "GPU")]
+    gpu_memory: u64,
+    /// 开启线程流
+    #[arg(long, requires = "gpu", help_heading = "GPU")]
+    gpu_multi_stream: bool,
+    /// 启用 CUDNN
+    #[arg(long, requires = "gpu", help_heading = "GPU")]
+    cudnn: bool,
+
+    /// 启用 TensorRT，动态 Shape 只能通过配置文件设置
+    #[arg(long, requires = "gpu", help_heading = "TensorRT")]
+    trt: bool,
+    /// TensorRT 使用的工作空间大小
+    #[arg(long, default_value_t = 1 << 30, requires = "trt", help_heading = "TensorRT")]
+    trt_workspace_size: i32,
+    /// 最大的 batch 大小
+    #[arg(long, default_value_t = 1, requires = "trt", help_heading = "TensorRT")]
+    trt_max_batch_size: i32,
+    /// 子图内节点个数大于该值时才使用 TensorRT 运行
+    #[arg(long, default_value_t = 3, requires = "trt", help_heading = "TensorRT")]
+    trt_min_subgraph_size: i32,
+    /// TensorRT 的精度，可选`fp32`、`fp16`、`int8`
+    #[arg(long, default_value = "fp32", value_parser = parse_precision, requires = "trt", help_heading = "TensorRT")]
+    trt_precision: PrecisionType,
+    /// 将 TensorRT 的优化信息序列化到磁盘
+    #[arg(long, requires = "trt", help_heading = "TensorRT")]
+    trt_static: bool,
+    /// 运行 INT8 离线量化校准
+    #[arg(long, requires = "trt", help_heading = "TensorRT")]
+    trt_calib: bool,
+    /// 启用 TensorRT OSS
+    #[arg(long, requires = "trt", help_heading = "TensorRT")]
+    trt_oss: bool,
+    /// 启用 TensorRT DLA，值为 DLA 设备id
+    #[arg(long, requires = "trt", help_heading = "TensorRT")]
+    trt_dla: Option<i32>,
+    /// 使用`--collect-shape-range-info`收集的 shape 范围文件作为动态 Shape
+    #[arg(long, requires = "trt", help_heading = "TensorRT")]
+    trt_tuned_shape: Option<String>,
+    /// 输入 shape 超出收集的范围时允许在运行时重新构建 TensorRT 引擎
+    #[arg(long, requires = "trt_tuned_shape", help_heading = "TensorRT")]
+    trt_allow_build_at_runtime: bool,
+
+    /// 使用 XPU 预测
+    #[arg(long, help_heading = "XPU")]
+    xpu: bool,
+    /// l3 cache 分配的显存大小，最大为16M
+    #[arg(
+        long,
+        default_value_t = 0xfffc00,
+        requires = "xpu",
+        help_heading = "XPU"
+    )]
+    xpu_l3_workspace_size: i32,
+    /// 锁定分配的 l3 cache
+    #[arg(long, requires = "xpu", help_heading = "XPU")]
+    xpu_locked: bool,
+    /// 对 conv 算子进行 autotune
+    #[arg(long, requires = "xpu", help_heading = "XPU")]
+    xpu_autotune: bool,
+    /// autotune 文件路径
+    #[arg(long, requires = "xpu", help_heading = "XPU")]
+    xpu_autotune_file: Option<String>,
+    /// multi_encoder 的计算精度
+    #[arg(long, default_value = "int16", requires = "xpu", help_heading = "XPU")]
+    xpu_precision: String,
+    /// multi_encoder 的输入可变长
+    #[arg(long, requires = "xpu", help_heading = "XPU")]
+    xpu_adaptive_seqlen: bool,
+
+    /// 使用 ONNXRuntime 预测
+    #[arg(long, help_heading = "ONNXRuntime")]
+    onnx_runtime: bool,
+    /// 开启 ONNXRuntime 优化
+    #[arg(long, requires = "onnx_runtime", help_heading = "ONNXRuntime")]
+    ort_optimization: bool,
+
+    /// 启用 Lite 子图
+    #[arg(long, help_heading = "Lite")]
+    lite: bool,
+    /// Lite 子图的运行精度，可选`fp32`、`fp16`、`int8`
+    #[arg(long, default_value = "fp32", value_parser = parse_precision, requires = "lite", help_heading = "Lite")]
+    lite_precision: PrecisionType,
+    /// Lite 子图与 paddle inference 之间共享数据
+    #[arg(long, requires = "lite", help_heading = "Lite")]
+    lite_zero_copy: bool,
+    /// Lite 子图的 pass 名称列表，以`,`分隔
+    #[arg(long, value_delimiter = ',', requires = "lite", help_heading = "Lite")]
+    lite_passes_filter: Vec<String>,
+    /// 不使用 Lite 子图运行的 op 名称列表，以`,`分隔
+    #[arg(long, value_delimiter = ',', requires = "lite", help_heading = "Lite")]
+    lite_ops_filter: Vec<String>,
+
+    /// 关闭 IR 优化
+    #[arg(long, help_heading = "IR 优化")]
+    no_ir_optim: bool,
+    /// 在每一个 PASS 后生成 dot 文件
+    #[arg(long, help_heading = "IR 优化")]
+    ir_debug: bool,
+    /// 删除的 pass，可以重复设置
+    #[arg(long, value_name = "PASS", help_heading = "IR 优化")]
+    delete_pass: Vec<String>,
+    /// 插入的 pass，格式为`位置:名称`，可以重复设置
+    #[arg(long, value_name = "IDX:PASS", value_parser = parse_insert_pass, help_heading = "IR 优化")]
+    insert_pass: Vec<(usize, String)>,
+    /// 追加到末尾的 pass，可以重复设置
+    #[arg(long, value_name = "PASS", help_heading = "IR 优化")]
+    append_pass: Vec<String>,
+
+    /// 开启内存/显存复用
+    #[arg(long, help_heading = "其他")]
+    memory_optim: bool,
+    /// 优化缓存路径
+    #[arg(long, help_heading = "其他")]
+    optim_cache_dir: Option<String>,
+    /// 收集运行时输入的 shape 范围并保存到该文件
+    #[arg(long, value_name = "PATH", help_heading = "其他")]
+    collect_shape_range_info: Option<String>,
+    /// 禁用 FC Padding
+    #[arg(long, help_heading = "其他")]
+    disable_fc_padding: bool,
+    /// 打开 Profile，`run`会在结束时打印各运算的耗时统计
+    #[arg(long, help_heading = "其他")]
+    profile: bool,
+    /// 去除 Paddle Inference 运行中的 LOG
+    #[arg(long, help_heading = "其他")]
+    disable_log: bool,
+}
+
+fn parse_precision(s: &str) -> Result<PrecisionType, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "fp32" | "float32" => Ok(PrecisionType::Float32),
+        "fp16" | "half" => Ok(PrecisionType::Half),
+        "int8" => Ok(PrecisionType::Int8),
+        _ => Err(format!("未知的精度`{}`，可选`fp32`、`fp16`、`int8`", s)),
+    }
+}
+
+fn parse_insert_pass(s: &str) -> Result<(usize, String), String> {
+    let (idx, pass) = s
+        .split_once(':')
+        .ok_or_else(|| format!("`{}`的格式应为`位置:名称`", s))?;
+    let idx = idx.parse().map_err(|_| format!("无效的位置`{}`", idx))?;
+    Ok((idx, pass.to_string()))
+}
+
+impl ConfigArgs {
+    /// 读取配置文件并应用命令行参数
+    pub fn config(&self) -> Result<Config, String> {
+        let model = match (&self.model_dir, &self.model, &self.params) {
+            (Some(dir), _, _) => Some(Model::dir(dir)),
+            (None, Some(model), Some(params)) => Some(Model::path(model, params)),
+            _ => None,
+        };
+        let mut config = match (&self.config, model) {
+            (Some(path), model) => {
+                let text = std::fs::read_to_string(path)
+                    .map_err(|e| format!("读取配置文件`{}`失败: {}", path.display(), e))?;
+                let mut config: Config = toml::from_str(&text)
+                    .map_err(|e| format!("解析配置文件`{}`失败: {}", path.display(), e))?;
+                if let Some(model) = model {
+                    config.model = model;
+                }
+                config
+            }
+            (None, Some(model)) => Config::new(model),
+            (None, None) => {
+                return Err(
+                    "需要通过`--model-dir`、`--model`及`--params`或`--config`设置模型".into(),
+                )
+            }
+        };
+
+        if let Some(path) = &self.library {
+            let runtime = Runtime::load(path).map_err(|e| e.to_string())?;
+            config = config.runtime(runtime);
+        }
+
+        self.apply_cpu(&mut config.cpu);
+        if let Some(device_id) = self.gpu {
+            config.gpu = Some(Gpu {
+                memory_pool_init_size_mb: self.gpu_memory,
+                device_id,
+                enable_multi_stream: self.gpu_multi_stream,
+                enable_cudnn: self.cudnn,
+                enable_tensor_rt: self.trt.then(|| self.tensor_rt()),
+            });
+        }
+        if self.xpu {
+            config.xpu = Some(Xpu {
+                l3_workspace_size: self.xpu_l3_workspace_size,
+                locked: self.xpu_locked,
+                autorune: self.xpu_autotune,
+                autotune_file: self.xpu_autotune_file.clone(),
+                precision: self.xpu_precision.clone(),
+                adaptive_seqlen: self.xpu_adaptive_seqlen,
+            });
+        }
+        if self.onnx_runtime {
+            config.onnx_runtime = Some(ONNXRuntime {
+                enable_optimization: self.ort_optimization,
+            });
+        }
+        if self.lite {
+            config.lite = Some(LiteEngine {
+                precision: self.lite_precision,
+                zero_copy: self.lite_zero_copy,
+                passes_filter: self.lite_passes_filter.clone(),
+                ops_filter: self.lite_ops_filter.clone(),
+            });
+        }
+
+        if self.no_ir_optim {
+            config.ir_optimization = false;
+        }
+        if self.ir_debug {
+            config.ir_debug = true;
+        }
+        let passes = &mut config.passes;
+        passes.delete.extend(self.delete_pass.iter().cloned());
+        passes.insert.extend(self.insert_pass.iter().cloned());
+        passes.append.extend(self.append_pass.iter().cloned());
+
+        if self.memory_optim {
+            config.memory_optimization = true;
+        }
+        if let Some(dir) = &self.optim_cache_dir {
+            config.optimization_cache_dir = Some(dir.clone());
+        }
+        if let Some(path) = &self.collect_shape_range_info {
+            config.collect_shape_range_info = Some(path.clone());
+        }
+        if self.disable_fc_padding {
+            config.disable_fc_padding = true;
+        }
+        if self.profile {
+            config.profile = true;
+        }
+        if self.disable_log {
+            config.disable_log = true;
+        }
+        Ok(config)
+    }
+
+    fn apply_cpu(&self, cpu: &mut Cpu) {
+        if self.threads.is_some() {
+            cpu.threads = self.threads;
+        }
+        if self.mkldnn {
+            let mkldnn = cpu.mkldnn.get_or_insert_with(Mkldnn::default);
+            if self.mkldnn_cache_size.is_some() {
+                mkldnn.cache_size = self.mkldnn_cache_size;
+            }
+            if self.mkldnn_op.is_some() {
+                mkldnn.op = self.mkldnn_op.clone();
+            }
+            if self.mkldnn_bf16_op.is_some() {
+                mkldnn.op_f16 = self.mkldnn_bf16_op.clone();
+            }
+        }
+    }
+
+    fn tensor_rt(&self) -> TensorRT {
+        TensorRT {
+            workspace_size: self.trt_workspace_size,
+            max_batch_size: self.trt_max_batch_size,
+            min_subgraph_size: self.trt_min_subgraph_size,
+            precision_type: self.trt_precision,
+            use_static: self.trt_static,
+            use_calib_mode: self.trt_calib,
+            dynamic_shape_info: vec![],
+            disable_plugin_fp16: false,
+            enable_oss: self.trt_oss,
+            dla_core: self.trt_dla,
+            tuned_dynamic_shape: self.trt_tuned_shape.clone().map(|shape_range_info_path| {
+                TunedDynamicShape {
+                    shape_range_info_path,
+                    allow_build_at_runtime: self.trt_allow_build_at_runtime,
+                }
+            }),
+        }
+    }
+}
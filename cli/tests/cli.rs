@@ -0,0 +1,214 @@
+use paddle_inference::{npy, HostTensor, Runtime};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+const ADD_MODEL: &str = "
+input x float32
+input y float32
+output sum = add x y
+output copy = identity x
+";
+
+/// 创建仅供当前测试使用的临时目录，并写入模型文件
+fn model_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("paddle-infer-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("__model__"), ADD_MODEL).unwrap();
+    dir
+}
+
+/// 使用模拟动态库运行`paddle-infer`
+fn paddle_infer(args: &[&str]) -> Output {
+    let exe = std::env::current_exe().unwrap();
+    let dir = exe.parent().unwrap();
+    let runtime = Runtime::search([dir, dir.parent().unwrap()]).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_paddle-infer"))
+        .args(args)
+        .arg("--library")
+        .arg(runtime.path())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+/// 读取`.npy`文件中的 float32 数据
+fn read_f32(path: &Path) -> Vec<f32> {
+    npy::load(path).unwrap().into_vec().unwrap()
+}
+
+#[test]
+fn test_info() {
+    let dir = model_dir("info");
+    let output = stdout(&paddle_infer(&[
+        "info",
+        "--model-dir",
+        dir.to_str().unwrap(),
+        "--threads",
+        "2",
+        "--mkldnn",
+    ]));
+    assert!(output.contains("version: 2.4.2"), "{}", output);
+    // 未设置输入前运行时的 shape 为空
+    assert!(output.contains("  x: Float32 当前 shape []"), "{}", output);
+    assert!(
+        output.contains("  sum: Unknown 当前 shape []"),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("PD_ConfigSetCpuMathLibraryNumThreads"),
+        "{}",
+        output
+    );
+    assert!(output.contains("PD_ConfigEnableMKLDNN()"), "{}", output);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_passes() {
+    let dir = model_dir("passes");
+    let config = dir.join("config.toml");
+    std::fs::write(
+        &config,
+        format!(
+            r#"
+ir_optimization = true
+ir_debug = false
+memory_optimization = false
+disable_fc_padding = false
+profile = false
+disable_log = false
+
+[model]
+model_file_path = "{0}/__model__"
+params_file_path = "{0}/params"
+
+[cpu]
+
+[passes]
+delete = []
+insert = []
+append = ["from_config_pass"]
+"#,
+            dir.display()
+        ),
+    )
+    .unwrap();
+    let output = stdout(&paddle_infer(&[
+        "passes",
+        "--config",
+        config.to_str().unwrap(),
+        "--append-pass",
+        "from_cli_pass",
+    ]));
+    let passes = output.lines().collect::<Vec<_>>();
+    assert_eq!(
+        passes[passes.len() - 2..],
+        ["from_config_pass", "from_cli_pass"]
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_run() {
+    let dir = model_dir("run");
+    let output_dir = dir.join("outputs");
+    let x = HostTensor::new(vec![2], vec![1.5f32, 2.5]).unwrap();
+    npy::save(dir.join("x.npy"), &x).unwrap();
+
+    let output = stdout(&paddle_infer(&[
+        "run",
+        "--model-dir",
+        dir.to_str().unwrap(),
+        "--input",
+        &format!("x={}", dir.join("x.npy").display()),
+        "--fill",
+        "y=2:1",
+        "--output-dir",
+        output_dir.to_str().unwrap(),
+    ]));
+    assert!(output.contains("sum: Float32 [2]"), "{}", output);
+    assert!(!output.contains("elementwise_add"), "{}", output);
+    assert_eq!(read_f32(&output_dir.join("sum.npy")), [2.5, 3.5]);
+    assert_eq!(read_f32(&output_dir.join("copy.npy")), [1.5, 2.5]);
+
+    let output = stdout(&paddle_infer(&[
+        "run",
+        "--model-dir",
+        dir.to_str().unwrap(),
+        "--fill",
+        "x=2",
+        "--fill",
+        "y=2",
+        "--output-dir",
+        output_dir.to_str().unwrap(),
+        "--profile",
+    ]));
+    let table = output.split("Event Summary").last().unwrap();
+    assert!(table.contains("总耗时(ms)"), "{}", output);
+    assert!(table.contains("elementwise_add"), "{}", output);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_run_npz() {
+    let dir = model_dir("run_npz");
+    let inputs = [("x", [1.0f32, 2.0]), ("y", [3.0, 4.0])].map(|(name, data)| {
+        (
+            name.to_string(),
+            HostTensor::new(vec![2], data.to_vec()).unwrap(),
+        )
+    });
+    npy::save_npz(dir.join("inputs.npz"), &inputs).unwrap();
+
+    paddle_infer(&[
+        "run",
+        "--model-dir",
+        dir.to_str().unwrap(),
+        "--npz",
+        dir.join("inputs.npz").to_str().unwrap(),
+        "--output-dir",
+        dir.to_str().unwrap(),
+    ]);
+    assert_eq!(read_f32(&dir.join("sum.npy")), [4.0, 6.0]);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_bench() {
+    let dir = model_dir("bench");
+    let args = [
+        "bench",
+        "--model-dir",
+        dir.to_str().unwrap(),
+        "-s",
+        "x=2,2",
+        "-s",
+        "y=2,2:float32",
+        "--warmup",
+        "1",
+        "-n",
+        "5",
+        "-j",
+        "2",
+    ];
+    let text = stdout(&paddle_infer(&args));
+    assert!(text.contains("p99"), "{}", text);
+
+    let json = stdout(&paddle_infer(&[&args[..], &["--json"]].concat()));
+    let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(report["threads"], 2);
+    assert_eq!(report["iterations"], 10);
+    assert!(report["latency"]["p50"].as_f64().unwrap() >= 0.0);
+    std::fs::remove_dir_all(dir).unwrap();
+}
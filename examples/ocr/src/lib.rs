@@ -12,7 +12,7 @@ use paddle_inference::{ctypes::PlaceType, utils::hwc_to_chw, Predictor};
 
 /// 识别文本行
 pub fn recognize(
-    model: &Predictor,
+    model: &mut Predictor,
     image: &Mat,
     roi_list: &[Rect],
     batch_size: usize,
@@ -43,9 +43,11 @@ pub fn recognize(
     let bh = roi_list.len() as i32;
 
     // 获取模型输入
-    let input = model.input("x");
+    let mut input = model
+        .input("x")
+        .map_err(paddle_error(-2002, "获取模型输入失败"))?;
     input.reshape(&[bh, 3, H, width]);
-    let buff = input.as_mut_slice_f32(PlaceType::Cpu).unwrap();
+    let buff = input.as_mut_slice::<f32>(PlaceType::Cpu).unwrap();
 
     // 将识别区域转为模型输入格式并复制到模型输入中
     let size = 3usize * H as usize * width as usize;
@@ -78,40 +80,42 @@ pub fn recognize(
         hwc_to_chw(roi.data_typed()?, buff, width as usize, H as usize, 3);
     }
 
-    if model.run() {
-        let names = model.output_names();
-        let out = model.output(&names.get(0).unwrap());
-        let size = out.shape();
-        let chunks_size = size[2] as usize;
-        let line_chunks_size = size[1] as usize * chunks_size;
-
-        let mut data = vec![0.0f32; size.iter().fold(1usize, |s, v| s * *v as usize)];
-        out.copy_to_f32(&mut data);
-
-        let range = 1..lbaels.len();
-        let r = data
-            .chunks(line_chunks_size)
-            .map(|line| {
-                line.chunks(chunks_size)
-                    .filter_map(|c| {
-                        c.iter()
-                            .enumerate()
-                            .max_by_key(|(_, v)| (*v * 10000.0) as usize)
-                            .and_then(|(k, _)| range.contains(&k).then(|| lbaels[k - 1].as_str()))
-                    })
-                    .collect::<Vec<_>>()
-                    .join("")
-            })
-            .collect();
+    drop(input);
+    model.run().map_err(paddle_error(-2002, "识别文本行失败"))?;
+
+    let names = model.output_names();
+    let out = model
+        .output(&names.get(0).unwrap())
+        .map_err(paddle_error(-2002, "获取模型输出失败"))?;
+    let size = out.shape();
+    let chunks_size = size[2] as usize;
+    let line_chunks_size = size[1] as usize * chunks_size;
+
+    let mut data = vec![0.0f32; size.iter().fold(1usize, |s, v| s * *v as usize)];
+    out.copy_to::<f32>(&mut data)
+        .map_err(paddle_error(-2002, "获取模型输出失败"))?;
+
+    let range = 1..lbaels.len();
+    let r = data
+        .chunks(line_chunks_size)
+        .map(|line| {
+            line.chunks(chunks_size)
+                .filter_map(|c| {
+                    c.iter()
+                        .enumerate()
+                        .max_by_key(|(_, v)| (*v * 10000.0) as usize)
+                        .and_then(|(k, _)| range.contains(&k).then(|| lbaels[k - 1].as_str()))
+                })
+                .collect::<Vec<_>>()
+                .join("")
+        })
+        .collect();
 
-        Ok(r)
-    } else {
-        Err(opencv::Error::new(-2002, "识别文本行失败"))
-    }
+    Ok(r)
 }
 
 /// 匹配文本行位置
-pub fn detect(model: &Predictor, image: &Mat) -> Result<Vec<Rect>, opencv::Error> {
+pub fn detect(model: &mut Predictor, image: &Mat) -> Result<Vec<Rect>, opencv::Error> {
     // 获取输入图片大小
     let (w, h) = image.size().map(|s| (s.width, s.height))?;
     let width = w as usize;
@@ -135,9 +139,11 @@ pub fn detect(model: &Predictor, image: &Mat) -> Result<Vec<Rect>, opencv::Error
     )?;
 
     // 获取模型输入
-    let input = model.input("x");
+    let mut input = model
+        .input("x")
+        .map_err(paddle_error(-2001, "获取模型输入失败"))?;
     input.reshape(&[1, 3, in_h, in_w]);
-    let buff = input.as_mut_slice_f32(PlaceType::Cpu).unwrap();
+    let buff = input.as_mut_slice::<f32>(PlaceType::Cpu).unwrap();
 
     // 将输入图片转为模型输入格式并复制到模型输入中
     let mut det_mat_f32 = Mat::default();
@@ -154,53 +160,65 @@ pub fn detect(model: &Predictor, image: &Mat) -> Result<Vec<Rect>, opencv::Error
     );
 
     // 运行模型
-    if model.run() {
-        // 获取模型输出并复制到内存中
-        let names = model.output_names();
-        let out = model.output(&names.get(0).unwrap());
-        let mut img_f32 =
-            Mat::new_size_with_default(Size::new(in_w * in_h, 1), CV_32FC1, Default::default())?;
-        out.copy_to_f32(img_f32.data_typed_mut()?);
-
-        // 将输出转为Mat
-        let mut img = Mat::default();
-        img_f32
-            .reshape(1, in_h)?
-            .convert_to(&mut img, CV_8UC1, 255.0, 0.0)?;
-
-        // 查找轮廓
-        let mut points: Vector<Vector<Point2i>> = Default::default();
-        find_contours(
-            &img,
-            &mut points,
-            RETR_EXTERNAL,
-            CHAIN_APPROX_SIMPLE,
-            Default::default(),
-        )?;
+    drop(input);
+    model
+        .run()
+        .map_err(paddle_error(-2001, "定位文本行是失败"))?;
+
+    // 获取模型输出并复制到内存中
+    let names = model.output_names();
+    let out = model
+        .output(&names.get(0).unwrap())
+        .map_err(paddle_error(-2001, "获取模型输出失败"))?;
+    let mut img_f32 =
+        Mat::new_size_with_default(Size::new(in_w * in_h, 1), CV_32FC1, Default::default())?;
+    out.copy_to::<f32>(img_f32.data_typed_mut()?)
+        .map_err(paddle_error(-2001, "获取模型输出失败"))?;
+
+    // 将输出转为Mat
+    let mut img = Mat::default();
+    img_f32
+        .reshape(1, in_h)?
+        .convert_to(&mut img, CV_8UC1, 255.0, 0.0)?;
+
+    // 查找轮廓
+    let mut points: Vector<Vector<Point2i>> = Default::default();
+    find_contours(
+        &img,
+        &mut points,
+        RETR_EXTERNAL,
+        CHAIN_APPROX_SIMPLE,
+        Default::default(),
+    )?;
 
-        // 提取、筛选轮廓
-        let l = points
-            .into_iter()
-            .map(|l| bounding_rect(&l))
-            .filter_map(|r| {
-                r.ok().and_then(|mut rect| {
-                    let offset = rect.height;
-                    rect.x = (rect.x - offset).clamp(0, w);
-                    rect.width = (rect.width + offset * 2).min(w - rect.x);
-                    rect.y = (rect.y - offset).clamp(0, h);
-                    rect.height = (rect.height + offset * 2).min(h - rect.y);
-
-                    if rect.width < 4 || rect.height < 8 {
-                        None
-                    } else {
-                        Some(rect)
-                    }
-                })
+    // 提取、筛选轮廓
+    let l = points
+        .into_iter()
+        .map(|l| bounding_rect(&l))
+        .filter_map(|r| {
+            r.ok().and_then(|mut rect| {
+                let offset = rect.height;
+                rect.x = (rect.x - offset).clamp(0, w);
+                rect.width = (rect.width + offset * 2).min(w - rect.x);
+                rect.y = (rect.y - offset).clamp(0, h);
+                rect.height = (rect.height + offset * 2).min(h - rect.y);
+
+                if rect.width < 4 || rect.height < 8 {
+                    None
+                } else {
+                    Some(rect)
+                }
             })
-            .collect();
+        })
+        .collect();
 
-        Ok(l)
-    } else {
-        Err(opencv::Error::new(-2001, "定位文本行是失败"))
-    }
+    Ok(l)
+}
+
+/// 将 paddle_inference 的错误转为 opencv 的错误
+fn paddle_error(
+    code: i32,
+    message: &str,
+) -> impl FnOnce(paddle_inference::Error) -> opencv::Error + '_ {
+    move |e| opencv::Error::new(code, format!("{message}: {e}"))
 }
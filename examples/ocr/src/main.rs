@@ -18,8 +18,8 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     std::fs::create_dir_all(&args.det_result_dir)?;
 
-    let det = args.det_model();
-    let rec = args.rec_model();
+    let mut det = args.det_model()?;
+    let mut rec = args.rec_model()?;
 
     println!("已加载模型");
 
@@ -31,10 +31,10 @@ fn main() -> Result<(), Box<dyn Error>> {
     let image = imread(&args.image_path, IMREAD_COLOR)?;
 
     let t = Instant::now();
-    let mut roi_list = detect(&det, &image)?;
+    let mut roi_list = detect(&mut det, &image)?;
     roi_list.sort_by_key(|r| r.y);
 
-    for (idx, (line, roi)) in recognize(&rec, &image, &roi_list, 8, &labels)?
+    for (idx, (line, roi)) in recognize(&mut rec, &image, &roi_list, 8, &labels)?
         .into_iter()
         .zip(roi_list.iter())
         .enumerate()
@@ -119,7 +119,7 @@ impl Args {
         }
     }
 
-    fn model(&self, model: Model, cache_dir: &str) -> Predictor {
+    fn model(&self, model: Model, cache_dir: &str) -> paddle_inference::Result<Predictor> {
         let mut p = Predictor::builder(model)
             .cpu(self.cpu_config())
             .enable_memory_optimization()
@@ -133,14 +133,14 @@ impl Args {
         p.build()
     }
 
-    pub fn det_model(&self) -> Predictor {
+    pub fn det_model(&self) -> paddle_inference::Result<Predictor> {
         self.model(
             Model::path(&self.det_model_path, &self.det_params_path),
             &self.det_cache_dir,
         )
     }
 
-    pub fn rec_model(&self) -> Predictor {
+    pub fn rec_model(&self) -> paddle_inference::Result<Predictor> {
         self.model(
             Model::path(&self.rec_model_path, &self.rec_params_path),
             &self.rec_cahce_dir,
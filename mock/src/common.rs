@@ -0,0 +1,216 @@
+//! 与`pd_common.h`/`pd_types.h`对应的类型及数组函数
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+pub type PD_Bool = bool;
+
+/// Tensor 的数据类型
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[repr(i32)]
+pub enum DataType {
+    Unknown = -1,
+    Float32,
+    Int32,
+    Int64,
+    Uint8,
+    Int8,
+}
+
+impl DataType {
+    /// 解析模型脚本中的数据类型名称
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "float32" => Some(DataType::Float32),
+            "int32" => Some(DataType::Int32),
+            "int64" => Some(DataType::Int64),
+            "uint8" => Some(DataType::Uint8),
+            "int8" => Some(DataType::Int8),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[repr(i32)]
+pub enum PrecisionType {
+    Float32 = 0,
+    Int8,
+    Half,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[repr(i32)]
+pub enum PlaceType {
+    Unknown = -1,
+    Cpu,
+    Gpu,
+    Xpu,
+}
+
+#[repr(C)]
+pub struct PD_OneDimArrayInt32 {
+    pub size: usize,
+    pub data: *mut i32,
+}
+
+#[repr(C)]
+pub struct PD_OneDimArraySize {
+    pub size: usize,
+    pub data: *mut usize,
+}
+
+#[repr(C)]
+pub struct PD_OneDimArrayCstr {
+    pub size: usize,
+    pub data: *mut *mut c_char,
+}
+
+#[repr(C)]
+pub struct PD_Cstr {
+    pub size: usize,
+    pub data: *mut c_char,
+}
+
+#[repr(C)]
+pub struct PD_TwoDimArraySize {
+    pub size: usize,
+    pub data: *mut *mut PD_OneDimArraySize,
+}
+
+/// 将`Vec`转换为 C 数组，使用[`free_slice`]释放
+fn leak_slice<T>(v: Vec<T>) -> (usize, *mut T) {
+    let size = v.len();
+    (size, Box::into_raw(v.into_boxed_slice()) as *mut T)
+}
+
+unsafe fn free_slice<T>(size: usize, data: *mut T) -> Vec<T> {
+    Box::from_raw(std::ptr::slice_from_raw_parts_mut(data, size)).into_vec()
+}
+
+/// 读取 C 字符串，空指针返回空字符串
+pub unsafe fn to_string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(ptr).to_string_lossy().into_owned()
+    }
+}
+
+/// 读取长度为`size`的 C 字符串数组
+pub unsafe fn to_strings(size: usize, ptr: *mut *const c_char) -> Vec<String> {
+    if ptr.is_null() {
+        return vec![];
+    }
+    std::slice::from_raw_parts(ptr, size)
+        .iter()
+        .map(|s| to_string(*s))
+        .collect()
+}
+
+pub fn new_array_int32(v: Vec<i32>) -> *mut PD_OneDimArrayInt32 {
+    let (size, data) = leak_slice(v);
+    Box::into_raw(Box::new(PD_OneDimArrayInt32 { size, data }))
+}
+
+pub fn new_array_size(v: Vec<usize>) -> *mut PD_OneDimArraySize {
+    let (size, data) = leak_slice(v);
+    Box::into_raw(Box::new(PD_OneDimArraySize { size, data }))
+}
+
+pub fn new_array_cstr<I: IntoIterator<Item = S>, S: AsRef<str>>(v: I) -> *mut PD_OneDimArrayCstr {
+    let v = v
+        .into_iter()
+        .map(|s| new_c_string(s.as_ref()).into_raw())
+        .collect();
+    let (size, data) = leak_slice(v);
+    Box::into_raw(Box::new(PD_OneDimArrayCstr { size, data }))
+}
+
+pub fn new_two_dim_array_size(v: Vec<Vec<usize>>) -> *mut PD_TwoDimArraySize {
+    let v = v.into_iter().map(new_array_size).collect();
+    let (size, data) = leak_slice(v);
+    Box::into_raw(Box::new(PD_TwoDimArraySize { size, data }))
+}
+
+/// 读取`PD_TwoDimArraySize`，不释放内存
+pub unsafe fn read_two_dim_array_size(array: *const PD_TwoDimArraySize) -> Vec<Vec<usize>> {
+    if array.is_null() || (*array).data.is_null() {
+        return vec![];
+    }
+    let array = &*array;
+    std::slice::from_raw_parts(array.data, array.size)
+        .iter()
+        .map(|item| {
+            let item = &**item;
+            if item.data.is_null() {
+                vec![]
+            } else {
+                std::slice::from_raw_parts(item.data, item.size).to_vec()
+            }
+        })
+        .collect()
+}
+
+pub fn new_cstr(s: &str) -> *mut PD_Cstr {
+    let s = new_c_string(s);
+    let size = s.as_bytes_with_nul().len();
+    Box::into_raw(Box::new(PD_Cstr {
+        size,
+        data: s.into_raw(),
+    }))
+}
+
+/// 去掉字符串中的`\0`后转换为`CString`
+pub fn new_c_string(s: &str) -> CString {
+    CString::new(s.replace('\0', "")).unwrap()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_GetVersion() -> *const c_char {
+    c"version: 2.4.2\ncommit: mock\nbranch: mock".as_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_OneDimArrayInt32Destroy(array: *mut PD_OneDimArrayInt32) {
+    if !array.is_null() {
+        let array = Box::from_raw(array);
+        free_slice(array.size, array.data);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_OneDimArraySizeDestroy(array: *mut PD_OneDimArraySize) {
+    if !array.is_null() {
+        let array = Box::from_raw(array);
+        free_slice(array.size, array.data);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_OneDimArrayCstrDestroy(array: *mut PD_OneDimArrayCstr) {
+    if !array.is_null() {
+        let array = Box::from_raw(array);
+        for s in free_slice(array.size, array.data) {
+            drop(CString::from_raw(s));
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_TwoDimArraySizeDestroy(array: *mut PD_TwoDimArraySize) {
+    if !array.is_null() {
+        let array = Box::from_raw(array);
+        for item in free_slice(array.size, array.data) {
+            PD_OneDimArraySizeDestroy(item);
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_CstrDestroy(cstr: *mut PD_Cstr) {
+    if !cstr.is_null() {
+        let cstr = Box::from_raw(cstr);
+        drop(CString::from_raw(cstr.data));
+    }
+}
@@ -0,0 +1,613 @@
+//! `PD_Config*`函数
+//!
+//! 所有设置函数都会记录到[`Config::calls`]中，`PD_ConfigSummary`按调用顺序每行返回一条记录
+
+use crate::common::*;
+use std::ffi::CString;
+use std::os::raw::c_char;
+
+/// 默认启用的 pass
+const DEFAULT_PASSES: &[&str] = &[
+    "simplify_with_basic_ops_pass",
+    "layer_norm_fuse_pass",
+    "attention_lstm_fuse_pass",
+    "seqconv_eltadd_relu_fuse_pass",
+    "fc_lstm_fuse_pass",
+    "conv_bn_fuse_pass",
+    "conv_eltwiseadd_bn_fuse_pass",
+    "fc_fuse_pass",
+    "is_test_pass",
+];
+
+/// 模拟的`PD_Config`
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// 按顺序记录的设置函数调用，如`PD_ConfigEnableUseGpu(100, 0)`
+    pub calls: Vec<String>,
+    pub model_dir: CString,
+    pub prog_file: CString,
+    pub params_file: CString,
+    pub opt_cache_dir: CString,
+    pub model_buffer: Option<(Vec<u8>, Vec<u8>)>,
+    pub fc_padding: bool,
+    pub use_gpu: bool,
+    pub gpu_device_id: i32,
+    pub memory_pool_init_size_mb: i32,
+    pub fraction_of_gpu_memory: f32,
+    pub onnx_runtime: bool,
+    pub ort_optimization: bool,
+    pub use_xpu: bool,
+    pub use_npu: bool,
+    pub npu_device_id: i32,
+    pub cudnn: bool,
+    pub ir_optim: bool,
+    pub ir_debug: bool,
+    pub tensorrt: bool,
+    pub trt_dynamic_shape: bool,
+    pub trt_tuned_dynamic_shape: bool,
+    pub trt_allow_build_at_runtime: bool,
+    pub collect_shape_range_info: bool,
+    pub shape_range_info_path: CString,
+    pub trt_oss: bool,
+    pub trt_dla: bool,
+    pub lite: bool,
+    pub mkldnn: bool,
+    pub mkldnn_quantizer: bool,
+    pub mkldnn_bfloat16: bool,
+    pub cpu_math_library_num_threads: i32,
+    pub thread_local_stream: bool,
+    pub memory_optim: bool,
+    pub profile: bool,
+    pub glog_info_disabled: bool,
+    pub valid: bool,
+    pub passes: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            calls: vec![],
+            model_dir: CString::default(),
+            prog_file: CString::default(),
+            params_file: CString::default(),
+            opt_cache_dir: CString::default(),
+            model_buffer: None,
+            fc_padding: true,
+            use_gpu: false,
+            gpu_device_id: 0,
+            memory_pool_init_size_mb: 0,
+            fraction_of_gpu_memory: 0.0,
+            onnx_runtime: false,
+            ort_optimization: false,
+            use_xpu: false,
+            use_npu: false,
+            npu_device_id: 0,
+            cudnn: false,
+            ir_optim: true,
+            ir_debug: false,
+            tensorrt: false,
+            trt_dynamic_shape: false,
+            trt_tuned_dynamic_shape: false,
+            trt_allow_build_at_runtime: false,
+            collect_shape_range_info: false,
+            shape_range_info_path: CString::default(),
+            trt_oss: false,
+            trt_dla: false,
+            lite: false,
+            mkldnn: false,
+            mkldnn_quantizer: false,
+            mkldnn_bfloat16: false,
+            cpu_math_library_num_threads: 1,
+            thread_local_stream: false,
+            memory_optim: false,
+            profile: false,
+            glog_info_disabled: false,
+            valid: true,
+            passes: DEFAULT_PASSES.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+macro_rules! record {
+    ($config: expr, $name: ident $(, $arg: expr )*) => {{
+        let args: &[String] = &[ $( format!("{:?}", $arg) ),* ];
+        $config.calls.push(format!("{}({})", stringify!($name), args.join(", ")));
+    }};
+}
+
+/// 无参数的设置函数：记录调用并设置字段
+macro_rules! setter {
+    ($( $name: ident => $field: ident = $value: expr; )*) => {
+        $(
+            #[no_mangle]
+            pub unsafe extern "C" fn $name(pd_config: *mut Config) {
+                let config = &mut *pd_config;
+                record!(config, $name);
+                config.$field = $value;
+            }
+        )*
+    };
+}
+
+/// 返回字段值的查询函数
+macro_rules! getter {
+    ($( $name: ident => $field: ident: $ty: ty; )*) => {
+        $(
+            #[no_mangle]
+            pub unsafe extern "C" fn $name(pd_config: *mut Config) -> $ty {
+                (*pd_config).$field
+            }
+        )*
+    };
+}
+
+/// 返回字符串字段的查询函数
+macro_rules! str_getter {
+    ($( $name: ident => $field: ident; )*) => {
+        $(
+            #[no_mangle]
+            pub unsafe extern "C" fn $name(pd_config: *mut Config) -> *const c_char {
+                (*pd_config).$field.as_ptr()
+            }
+        )*
+    };
+}
+
+setter! {
+    PD_ConfigDisableFCPadding => fc_padding = false;
+    PD_ConfigDisableGpu => use_gpu = false;
+    PD_ConfigEnableONNXRuntime => onnx_runtime = true;
+    PD_ConfigDisableONNXRuntime => onnx_runtime = false;
+    PD_ConfigEnableORTOptimization => ort_optimization = true;
+    PD_ConfigEnableCudnn => cudnn = true;
+    PD_ConfigEnableTensorRtOSS => trt_oss = true;
+    PD_ConfigEnableMKLDNN => mkldnn = true;
+    PD_ConfigEnableMkldnnQuantizer => mkldnn_quantizer = true;
+    PD_ConfigEnableMkldnnBfloat16 => mkldnn_bfloat16 = true;
+    PD_ConfigEnableGpuMultiStream => thread_local_stream = true;
+    PD_ConfigEnableProfile => profile = true;
+    PD_ConfigDisableGlogInfo => glog_info_disabled = true;
+    PD_ConfigSetInvalid => valid = false;
+}
+
+getter! {
+    PD_ConfigUseFcPadding => fc_padding: PD_Bool;
+    PD_ConfigUseGpu => use_gpu: PD_Bool;
+    PD_ConfigONNXRuntimeEnabled => onnx_runtime: PD_Bool;
+    PD_ConfigUseXpu => use_xpu: PD_Bool;
+    PD_ConfigUseNpu => use_npu: PD_Bool;
+    PD_ConfigGpuDeviceId => gpu_device_id: i32;
+    PD_ConfigNpuDeviceId => npu_device_id: i32;
+    PD_ConfigMemoryPoolInitSizeMb => memory_pool_init_size_mb: i32;
+    PD_ConfigFractionOfGpuMemoryForPool => fraction_of_gpu_memory: f32;
+    PD_ConfigCudnnEnabled => cudnn: PD_Bool;
+    PD_ConfigIrOptim => ir_optim: PD_Bool;
+    PD_ConfigTensorRtEngineEnabled => tensorrt: PD_Bool;
+    PD_ConfigTensorRtDynamicShapeEnabled => trt_dynamic_shape: PD_Bool;
+    PD_ConfigTunedTensorRtDynamicShape => trt_tuned_dynamic_shape: PD_Bool;
+    PD_ConfigTrtAllowBuildAtRuntime => trt_allow_build_at_runtime: PD_Bool;
+    PD_ConfigShapeRangeInfoCollected => collect_shape_range_info: PD_Bool;
+    PD_ConfigTensorRtOssEnabled => trt_oss: PD_Bool;
+    PD_ConfigTensorRtDlaEnabled => trt_dla: PD_Bool;
+    PD_ConfigLiteEngineEnabled => lite: PD_Bool;
+    PD_ConfigMkldnnEnabled => mkldnn: PD_Bool;
+    PD_ConfigGetCpuMathLibraryNumThreads => cpu_math_library_num_threads: i32;
+    PD_ConfigMkldnnQuantizerEnabled => mkldnn_quantizer: PD_Bool;
+    PD_ConfigMkldnnBfloat16Enabled => mkldnn_bfloat16: PD_Bool;
+    PD_ConfigThreadLocalStreamEnabled => thread_local_stream: PD_Bool;
+    PD_ConfigMemoryOptimEnabled => memory_optim: PD_Bool;
+    PD_ConfigProfileEnabled => profile: PD_Bool;
+    PD_ConfigGlogInfoDisabled => glog_info_disabled: PD_Bool;
+    PD_ConfigIsValid => valid: PD_Bool;
+}
+
+str_getter! {
+    PD_ConfigGetModelDir => model_dir;
+    PD_ConfigGetProgFile => prog_file;
+    PD_ConfigGetParamsFile => params_file;
+}
+
+extern "C" {
+    fn malloc(size: usize) -> *mut c_char;
+}
+
+/// 与 paddle 相同，返回通过`malloc`复制的字符串，需要调用者使用`free`释放
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigShapeRangeInfoPath(pd_config: *mut Config) -> *const c_char {
+    let path = (*pd_config).shape_range_info_path.as_bytes_with_nul();
+    let ptr = malloc(path.len());
+    std::ptr::copy_nonoverlapping(path.as_ptr() as *const c_char, ptr, path.len());
+    ptr
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigCreate() -> *mut Config {
+    Box::into_raw(Box::default())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigDestroy(pd_config: *mut Config) {
+    if !pd_config.is_null() {
+        drop(Box::from_raw(pd_config));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigSetModel(
+    pd_config: *mut Config,
+    prog_file_path: *const c_char,
+    params_file_path: *const c_char,
+) {
+    let config = &mut *pd_config;
+    let (prog, params) = (to_string(prog_file_path), to_string(params_file_path));
+    record!(config, PD_ConfigSetModel, prog, params);
+    config.prog_file = new_c_string(&prog);
+    config.params_file = new_c_string(&params);
+    config.model_buffer = None;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigSetProgFile(
+    pd_config: *mut Config,
+    prog_file_path: *const c_char,
+) {
+    let config = &mut *pd_config;
+    let prog = to_string(prog_file_path);
+    record!(config, PD_ConfigSetProgFile, prog);
+    config.prog_file = new_c_string(&prog);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigSetParamsFile(
+    pd_config: *mut Config,
+    params_file_path: *const c_char,
+) {
+    let config = &mut *pd_config;
+    let params = to_string(params_file_path);
+    record!(config, PD_ConfigSetParamsFile, params);
+    config.params_file = new_c_string(&params);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigSetOptimCacheDir(
+    pd_config: *mut Config,
+    opt_cache_dir: *const c_char,
+) {
+    let config = &mut *pd_config;
+    let dir = to_string(opt_cache_dir);
+    record!(config, PD_ConfigSetOptimCacheDir, dir);
+    config.opt_cache_dir = new_c_string(&dir);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigSetModelDir(pd_config: *mut Config, model_dir: *const c_char) {
+    let config = &mut *pd_config;
+    let dir = to_string(model_dir);
+    record!(config, PD_ConfigSetModelDir, dir);
+    config.model_dir = new_c_string(&dir);
+    config.model_buffer = None;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigSetModelBuffer(
+    pd_config: *mut Config,
+    prog_buffer: *const c_char,
+    prog_buffer_size: usize,
+    params_buffer: *const c_char,
+    params_buffer_size: usize,
+) {
+    let config = &mut *pd_config;
+    record!(
+        config,
+        PD_ConfigSetModelBuffer,
+        prog_buffer_size,
+        params_buffer_size
+    );
+    let read = |ptr: *const c_char, size: usize| {
+        if ptr.is_null() {
+            vec![]
+        } else {
+            std::slice::from_raw_parts(ptr as *const u8, size).to_vec()
+        }
+    };
+    config.model_buffer = Some((
+        read(prog_buffer, prog_buffer_size),
+        read(params_buffer, params_buffer_size),
+    ));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigModelFromMemory(pd_config: *mut Config) -> PD_Bool {
+    (*pd_config).model_buffer.is_some()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigEnableUseGpu(
+    pd_config: *mut Config,
+    memory_pool_init_size_mb: u64,
+    device_id: i32,
+) {
+    let config = &mut *pd_config;
+    record!(
+        config,
+        PD_ConfigEnableUseGpu,
+        memory_pool_init_size_mb,
+        device_id
+    );
+    config.use_gpu = true;
+    config.gpu_device_id = device_id;
+    config.memory_pool_init_size_mb = memory_pool_init_size_mb as i32;
+    config.fraction_of_gpu_memory = 0.5;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigEnableXpu(
+    pd_config: *mut Config,
+    l3_workspace_size: i32,
+    locked: PD_Bool,
+    autotune: PD_Bool,
+    autotune_file: *const c_char,
+    precision: *const c_char,
+    adaptive_seqlen: PD_Bool,
+) {
+    let config = &mut *pd_config;
+    record!(
+        config,
+        PD_ConfigEnableXpu,
+        l3_workspace_size,
+        locked,
+        autotune,
+        to_string(autotune_file),
+        to_string(precision),
+        adaptive_seqlen
+    );
+    config.use_xpu = true;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigXpuDeviceId(_pd_config: *mut Config) -> i32 {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigEnableNpu(pd_config: *mut Config, device_id: i32) {
+    let config = &mut *pd_config;
+    record!(config, PD_ConfigEnableNpu, device_id);
+    config.use_npu = true;
+    config.npu_device_id = device_id;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigSwitchIrOptim(pd_config: *mut Config, x: PD_Bool) {
+    let config = &mut *pd_config;
+    record!(config, PD_ConfigSwitchIrOptim, x);
+    config.ir_optim = x;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigSwitchIrDebug(pd_config: *mut Config, x: PD_Bool) {
+    let config = &mut *pd_config;
+    record!(config, PD_ConfigSwitchIrDebug, x);
+    config.ir_debug = x;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigEnableTensorRtEngine(
+    pd_config: *mut Config,
+    workspace_size: i32,
+    max_batch_size: i32,
+    min_subgraph_size: i32,
+    precision: PrecisionType,
+    use_static: PD_Bool,
+    use_calib_mode: PD_Bool,
+) {
+    let config = &mut *pd_config;
+    record!(
+        config,
+        PD_ConfigEnableTensorRtEngine,
+        workspace_size,
+        max_batch_size,
+        min_subgraph_size,
+        precision,
+        use_static,
+        use_calib_mode
+    );
+    config.tensorrt = true;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigSetTrtDynamicShapeInfo(
+    pd_config: *mut Config,
+    tensor_num: usize,
+    tensor_name: *mut *const c_char,
+    shapes_num: *mut usize,
+    min_shape: *mut *mut i32,
+    max_shape: *mut *mut i32,
+    optim_shape: *mut *mut i32,
+    disable_trt_plugin_fp16: PD_Bool,
+) {
+    let config = &mut *pd_config;
+    let names = to_strings(tensor_num, tensor_name);
+    let read = |shapes: *mut *mut i32| -> Vec<Vec<i32>> {
+        (0..tensor_num)
+            .map(|i| std::slice::from_raw_parts(*shapes.add(i), *shapes_num.add(i)).to_vec())
+            .collect()
+    };
+    record!(
+        config,
+        PD_ConfigSetTrtDynamicShapeInfo,
+        names,
+        read(min_shape),
+        read(max_shape),
+        read(optim_shape),
+        disable_trt_plugin_fp16
+    );
+    config.trt_dynamic_shape = true;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigEnableTunedTensorRtDynamicShape(
+    pd_config: *mut Config,
+    shape_range_info_path: *const c_char,
+    allow_build_at_runtime: PD_Bool,
+) {
+    let config = &mut *pd_config;
+    let path = to_string(shape_range_info_path);
+    record!(
+        config,
+        PD_ConfigEnableTunedTensorRtDynamicShape,
+        path,
+        allow_build_at_runtime
+    );
+    config.trt_tuned_dynamic_shape = true;
+    config.trt_allow_build_at_runtime = allow_build_at_runtime;
+    config.shape_range_info_path = new_c_string(&path);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigCollectShapeRangeInfo(
+    pd_config: *mut Config,
+    shape_range_info_path: *const c_char,
+) {
+    let config = &mut *pd_config;
+    let path = to_string(shape_range_info_path);
+    record!(config, PD_ConfigCollectShapeRangeInfo, path);
+    config.collect_shape_range_info = true;
+    config.shape_range_info_path = new_c_string(&path);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigDisableTensorRtOPs(
+    pd_config: *mut Config,
+    ops_num: usize,
+    ops_name: *mut *const c_char,
+) {
+    let config = &mut *pd_config;
+    record!(
+        config,
+        PD_ConfigDisableTensorRtOPs,
+        to_strings(ops_num, ops_name)
+    );
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigEnableTensorRtDla(pd_config: *mut Config, dla_core: i32) {
+    let config = &mut *pd_config;
+    record!(config, PD_ConfigEnableTensorRtDla, dla_core);
+    config.trt_dla = true;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigEnableLiteEngine(
+    pd_config: *mut Config,
+    precision: PrecisionType,
+    zero_copy: PD_Bool,
+    passes_filter_num: usize,
+    passes_filter: *mut *const c_char,
+    ops_filter_num: usize,
+    ops_filter: *mut *const c_char,
+) {
+    let config = &mut *pd_config;
+    record!(
+        config,
+        PD_ConfigEnableLiteEngine,
+        precision,
+        zero_copy,
+        to_strings(passes_filter_num, passes_filter),
+        to_strings(ops_filter_num, ops_filter)
+    );
+    config.lite = true;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigSetMkldnnCacheCapacity(pd_config: *mut Config, capacity: i32) {
+    let config = &mut *pd_config;
+    record!(config, PD_ConfigSetMkldnnCacheCapacity, capacity);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigSetCpuMathLibraryNumThreads(
+    pd_config: *mut Config,
+    cpu_math_library_num_threads: i32,
+) {
+    let config = &mut *pd_config;
+    record!(
+        config,
+        PD_ConfigSetCpuMathLibraryNumThreads,
+        cpu_math_library_num_threads
+    );
+    config.cpu_math_library_num_threads = cpu_math_library_num_threads;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigSetMkldnnOp(
+    pd_config: *mut Config,
+    ops_num: usize,
+    op_list: *mut *const c_char,
+) {
+    let config = &mut *pd_config;
+    record!(config, PD_ConfigSetMkldnnOp, to_strings(ops_num, op_list));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigSetBfloat16Op(
+    pd_config: *mut Config,
+    ops_num: usize,
+    op_list: *mut *const c_char,
+) {
+    let config = &mut *pd_config;
+    record!(config, PD_ConfigSetBfloat16Op, to_strings(ops_num, op_list));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigEnableMemoryOptim(pd_config: *mut Config, x: PD_Bool) {
+    let config = &mut *pd_config;
+    record!(config, PD_ConfigEnableMemoryOptim, x);
+    config.memory_optim = x;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigPartiallyRelease(pd_config: *mut Config) {
+    let config = &mut *pd_config;
+    record!(config, PD_ConfigPartiallyRelease);
+    config.model_buffer = None;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigDeletePass(pd_config: *mut Config, pass: *const c_char) {
+    let config = &mut *pd_config;
+    let pass = to_string(pass);
+    record!(config, PD_ConfigDeletePass, pass);
+    config.passes.retain(|p| *p != pass);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigInsertPass(
+    pd_config: *mut Config,
+    idx: usize,
+    pass: *const c_char,
+) {
+    let config = &mut *pd_config;
+    let pass = to_string(pass);
+    record!(config, PD_ConfigInsertPass, idx, pass);
+    let idx = idx.min(config.passes.len());
+    config.passes.insert(idx, pass);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigAppendPass(pd_config: *mut Config, pass: *const c_char) {
+    let config = &mut *pd_config;
+    let pass = to_string(pass);
+    record!(config, PD_ConfigAppendPass, pass);
+    config.passes.push(pass);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigAllPasses(pd_config: *mut Config) -> *mut PD_OneDimArrayCstr {
+    new_array_cstr(&(*pd_config).passes)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_ConfigSummary(pd_config: *mut Config) -> *mut PD_Cstr {
+    new_cstr(&(*pd_config).calls.join("\n"))
+}
@@ -0,0 +1,20 @@
+//! 用于离线测试的`paddle_inference_c`模拟动态库
+//!
+//! 导出与[paddle inference C API](https://www.paddlepaddle.org.cn/inference/master/api_reference/c_api_doc/c_api_index.html)
+//! 同名的`PD_*`函数，编译为`libpaddle_inference_c.so`后可以通过`paddle_inference::Runtime::load`加载：
+//! - `PD_Config*`记录所有设置函数的调用，`PD_ConfigSummary`按调用顺序每行返回一条记录
+//! - `PD_ConfigShapeRangeInfoPath`与 paddle 相同返回通过`malloc`复制的字符串，需要调用者释放
+//! - Tensor 的数据保存在内存中，同一个 Predictor 中同名的 Tensor 共享数据
+//! - 模型为文本脚本，格式见[`model`]
+//! - 启用 Profile 时，`PD_PredictorDestroy`按 paddle 的格式向标准输出打印各运算的耗时
+//! - `PD_PredictorTryShrinkMemory`返回上次调用后单次预测中输入及输出占用字节数的峰值，并将其清零
+// 导出函数的参数均为 C 接口传入的指针，安全要求与 paddle inference C API 相同
+#![allow(clippy::missing_safety_doc)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+
+pub mod common;
+pub mod config;
+pub mod model;
+pub mod predictor;
+pub mod tensor;
@@ -0,0 +1,163 @@
+//! 模拟模型
+//!
+//! 模型文件（或`__model__`、模型内存）为文本脚本，每行一条语句，`#`开头的行为注释：
+//!
+//! ``` text
+//! # 声明输入及其数据类型：float32、int32、int64、uint8、int8
+//! input x float32
+//! input y float32
+//! # 声明输出及其计算方式，参数可以为输入或之前声明的输出
+//! output sum = add x y
+//! output copy = identity x
+//! # 可选，所有预测器最多被克隆的次数，超过后`PD_PredictorClone`返回空指针
+//! max_clones 1
+//! ```
+//!
+//! 支持的运算：
+//! - `identity a`：复制`a`的 shape、数据及 LoD
+//! - `add a b`：逐元素相加，`a`和`b`的数据类型及 shape 必须相同
+//! - `sleep a ms`：等待`ms`毫秒后复制`a`，用于模拟耗时的预测
+
+use crate::common::DataType;
+use crate::tensor::{Buffer, TensorData};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// 输出的计算方式
+#[derive(Debug, Clone, PartialEq)]
+pub enum Op {
+    Identity(String),
+    Add(String, String),
+    Sleep(String, u64),
+}
+
+impl Op {
+    /// 对应的 paddle 运算类型，用于 profile 报告
+    pub fn op_type(&self) -> &'static str {
+        match self {
+            Op::Identity(_) => "assign",
+            Op::Add(..) => "elementwise_add",
+            Op::Sleep(..) => "sleep",
+        }
+    }
+}
+
+/// 解析后的模型
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Model {
+    pub inputs: Vec<(String, DataType)>,
+    pub outputs: Vec<(String, Op)>,
+    pub max_clones: Option<usize>,
+}
+
+impl Model {
+    pub fn parse(script: &str) -> Result<Self, String> {
+        let mut model = Model::default();
+        for (i, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = || format!("第{}行无法解析: {}", i + 1, line);
+            let tokens: Vec<_> = line.split_whitespace().collect();
+            match tokens.as_slice() {
+                ["input", name, data_type] => {
+                    let data_type = DataType::parse(data_type).ok_or_else(error)?;
+                    model.inputs.push((name.to_string(), data_type));
+                }
+                ["output", name, "=", op @ ..] => {
+                    let op = match op {
+                        ["identity", a] => Op::Identity(a.to_string()),
+                        ["add", a, b] => Op::Add(a.to_string(), b.to_string()),
+                        ["sleep", a, ms] => {
+                            Op::Sleep(a.to_string(), ms.parse().map_err(|_| error())?)
+                        }
+                        _ => return Err(error()),
+                    };
+                    model.outputs.push((name.to_string(), op));
+                }
+                ["max_clones", n] => model.max_clones = Some(n.parse().map_err(|_| error())?),
+                _ => return Err(error()),
+            }
+        }
+
+        if model.inputs.is_empty() || model.outputs.is_empty() {
+            return Err("模型至少需要一个输入和一个输出".to_string());
+        }
+        Ok(model)
+    }
+
+    /// 根据输入计算所有输出，输入未设置或数据不匹配时返回错误
+    pub fn run(
+        &self,
+        inputs: HashMap<String, TensorData>,
+    ) -> Result<HashMap<String, TensorData>, String> {
+        self.run_with(inputs, |_, _| {})
+    }
+
+    /// 与[`Self::run`]相同，每个运算完成后使用运算及其耗时调用`on_op`
+    pub fn run_with(
+        &self,
+        inputs: HashMap<String, TensorData>,
+        mut on_op: impl FnMut(&Op, Duration),
+    ) -> Result<HashMap<String, TensorData>, String> {
+        let mut values = inputs;
+        for (name, data) in &values {
+            if !data.is_ready() {
+                return Err(format!("输入`{}`未设置数据", name));
+            }
+        }
+
+        let mut outputs = HashMap::new();
+        for (name, op) in &self.outputs {
+            let get = |name: &str| {
+                values
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| format!("`{}`不存在", name))
+            };
+            let start = Instant::now();
+            let data = match op {
+                Op::Identity(a) => get(a)?,
+                Op::Add(a, b) => add(get(a)?, get(b)?)?,
+                Op::Sleep(a, ms) => {
+                    std::thread::sleep(Duration::from_millis(*ms));
+                    get(a)?
+                }
+            };
+            on_op(op, start.elapsed());
+            values.insert(name.clone(), data.clone());
+            outputs.insert(name.clone(), data);
+        }
+        Ok(outputs)
+    }
+}
+
+fn add(a: TensorData, b: TensorData) -> Result<TensorData, String> {
+    if a.shape != b.shape {
+        return Err(format!("shape 不匹配: {:?}, {:?}", a.shape, b.shape));
+    }
+
+    macro_rules! add {
+        ($( $variant: ident ),*) => {
+            match (a.buffer, b.buffer) {
+                (Some(Buffer::Float32(x)), Some(Buffer::Float32(y))) => {
+                    Buffer::Float32(x.iter().zip(&y).map(|(x, y)| x + y).collect())
+                }
+                $(
+                    (Some(Buffer::$variant(x)), Some(Buffer::$variant(y))) => {
+                        Buffer::$variant(x.iter().zip(&y).map(|(x, y)| x.wrapping_add(*y)).collect())
+                    }
+                )*
+                _ => return Err("数据类型不匹配".to_string()),
+            }
+        };
+    }
+
+    let buffer = add!(Int32, Int64, Uint8, Int8);
+    Ok(TensorData {
+        shape: a.shape,
+        buffer: Some(buffer),
+        lod: a.lod,
+    })
+}
@@ -0,0 +1,331 @@
+//! `PD_Predictor*`函数
+
+use crate::common::*;
+use crate::config::Config;
+use crate::model::Model;
+use crate::tensor::{Handle, TensorData};
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+type Slot = (CString, Arc<Mutex<TensorData>>);
+
+/// 模拟的`PD_Predictor`
+pub struct Predictor {
+    pub config: Config,
+    pub model: Model,
+    inputs: Vec<Slot>,
+    outputs: Vec<Slot>,
+    /// 启用 shape 范围收集时，每个输入出现过的 shape 及其次数
+    shapes: Mutex<BTreeMap<String, BTreeMap<Vec<i32>, usize>>>,
+    /// 模拟的内存池大小，即上次释放后单次预测中输入及输出占用字节数的峰值
+    pool: Mutex<u64>,
+    /// 启用 profile 时，每种运算每次执行的耗时
+    profile: Mutex<BTreeMap<&'static str, Vec<Duration>>>,
+    /// 与克隆出的预测器共享的克隆次数
+    clones: Arc<AtomicUsize>,
+}
+
+impl Predictor {
+    /// 读取`config`中设置的模型，读取或解析失败时返回错误
+    pub fn new(config: Config) -> Result<Self, String> {
+        if !config.valid {
+            return Err("config 已失效".to_string());
+        }
+
+        let script = if let Some((prog, _)) = &config.model_buffer {
+            String::from_utf8_lossy(prog).into_owned()
+        } else if !config.prog_file.as_bytes().is_empty() {
+            read(Path::new(&*config.prog_file.to_string_lossy()))?
+        } else if !config.model_dir.as_bytes().is_empty() {
+            read(&Path::new(&*config.model_dir.to_string_lossy()).join("__model__"))?
+        } else {
+            return Err("未设置模型".to_string());
+        };
+        let model = Model::parse(&script)?;
+        Ok(Self::with_model(config, model))
+    }
+
+    fn with_model(config: Config, model: Model) -> Self {
+        let slot = |name: &str, data| (new_c_string(name), Arc::new(Mutex::new(data)));
+        let inputs = model
+            .inputs
+            .iter()
+            .map(|(name, data_type)| slot(name, TensorData::new(*data_type)))
+            .collect();
+        let outputs = model
+            .outputs
+            .iter()
+            .map(|(name, _)| slot(name, TensorData::default()))
+            .collect();
+        Self {
+            config,
+            model,
+            inputs,
+            outputs,
+            shapes: Default::default(),
+            pool: Default::default(),
+            profile: Default::default(),
+            clones: Default::default(),
+        }
+    }
+
+    fn run(&self) -> Result<(), String> {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|(name, data)| {
+                let data = data.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                (name.to_string_lossy().into_owned(), data)
+            })
+            .collect::<HashMap<_, _>>();
+        let input_shapes = inputs
+            .iter()
+            .map(|(name, data)| (name.clone(), data.shape.clone()))
+            .collect::<Vec<_>>();
+        let bytes = |data: &HashMap<String, TensorData>| {
+            data.values()
+                .filter_map(|data| data.buffer.as_ref())
+                .map(|buffer| buffer.bytes() as u64)
+                .sum::<u64>()
+        };
+        let input_bytes = bytes(&inputs);
+        let mut timings = vec![];
+        let mut outputs = self
+            .model
+            .run_with(inputs, |op, time| timings.push((op.op_type(), time)))?;
+        if self.config.profile {
+            let mut profile = self.profile.lock().unwrap_or_else(|e| e.into_inner());
+            for (op_type, time) in timings {
+                profile.entry(op_type).or_default().push(time);
+            }
+        }
+        let bytes = input_bytes + bytes(&outputs);
+        let mut pool = self.pool.lock().unwrap_or_else(|e| e.into_inner());
+        *pool = (*pool).max(bytes);
+        drop(pool);
+
+        if self.config.collect_shape_range_info {
+            let mut shapes = self.shapes.lock().unwrap_or_else(|e| e.into_inner());
+            for (name, shape) in input_shapes {
+                *shapes.entry(name).or_default().entry(shape).or_default() += 1;
+            }
+        }
+        for (name, data) in &self.outputs {
+            if let Some(output) = outputs.remove(&*name.to_string_lossy()) {
+                *data.lock().unwrap_or_else(|e| e.into_inner()) = output;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Predictor {
+    /// 与 paddle 相同，在销毁时保存收集的 shape 范围并打印 profile 报告
+    fn drop(&mut self) {
+        if self.config.collect_shape_range_info {
+            self.save_shape_range_info();
+        }
+        if self.config.profile {
+            self.print_profile();
+        }
+    }
+}
+
+impl Predictor {
+    fn save_shape_range_info(&mut self) {
+        let shapes = self.shapes.get_mut().unwrap_or_else(|e| e.into_inner());
+        let mut text = String::new();
+        for (name, shapes) in shapes.iter() {
+            let dims = shapes.keys().map(Vec::len).max().unwrap_or(0);
+            let fold = |f: fn(i32, i32) -> i32| {
+                (0..dims)
+                    .map(|i| {
+                        shapes
+                            .keys()
+                            .filter_map(|s| s.get(i).copied())
+                            .reduce(f)
+                            .unwrap_or(0)
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let (opt, _) = shapes.iter().max_by_key(|(_, count)| **count).unwrap();
+            text += &format!("shape_range_info {{\n  name: \"{}\"\n", name);
+            for (key, shape) in [
+                ("min_shape", fold(i32::min)),
+                ("max_shape", fold(i32::max)),
+                ("opt_shape", opt.clone()),
+            ] {
+                for dim in shape {
+                    text += &format!("  {}: {}\n", key, dim);
+                }
+            }
+            text += "}\n";
+        }
+        let _ = std::fs::write(&*self.config.shape_range_info_path.to_string_lossy(), text);
+    }
+
+    /// 按 paddle 的格式向标准输出打印 profile 报告，每种运算包含一条`/compute`子事件
+    fn print_profile(&mut self) {
+        let profile = self.profile.get_mut().unwrap_or_else(|e| e.into_inner());
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let total = profile.values().flatten().copied().map(ms).sum::<f64>();
+        let mut events = profile
+            .iter()
+            .map(|(op_type, times)| (*op_type, times.iter().copied().map(ms).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        events.sort_by(|a, b| b.1.iter().sum::<f64>().total_cmp(&a.1.iter().sum::<f64>()));
+
+        let mut text = String::new();
+        text +=
+            "------------------------->     Profiling Report     <-------------------------\n\n";
+        text += "Place: All\nTime unit: ms\n";
+        text += "Sorted by total time in descending order in the same thread\n\n";
+        text += "-------------------------     Overhead Summary      -------------------------\n\n";
+        text += &format!("Total time: {}\n", total);
+        text += &format!(
+            "  Computation time       Total: {}     Ratio: 100%\n\n",
+            total
+        );
+        text += "-------------------------       Event Summary       -------------------------\n\n";
+        text += &format!(
+            "{:<40}{:<12}{:<12}{:<12}{:<12}{:<12}{:<12}\n",
+            "Event", "Calls", "Total", "Min.", "Max.", "Ave.", "Ratio."
+        );
+        for (op_type, times) in events {
+            let sum = times.iter().sum::<f64>();
+            let min = times.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = times.iter().copied().fold(0.0, f64::max);
+            let avg = sum / times.len() as f64;
+            let ratio = if total > 0.0 { sum / total } else { 0.0 };
+            for name in [
+                format!("thread0::{}", op_type),
+                format!("thread0::{}/compute", op_type),
+            ] {
+                text += &format!(
+                    "{:<40}{:<12}{:<12.6}{:<12.6}{:<12.6}{:<12.6}{:<12.6}\n",
+                    name,
+                    times.len(),
+                    sum,
+                    min,
+                    max,
+                    avg,
+                    ratio
+                );
+            }
+        }
+        print!("{}", text);
+        let _ = std::io::Write::flush(&mut std::io::stdout());
+    }
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("读取模型`{}`失败: {}", path.display(), e))
+}
+
+unsafe fn handle(slots: &[Slot], name: *const c_char) -> *mut Handle {
+    let name = to_string(name);
+    slots
+        .iter()
+        .find(|(n, _)| n.to_string_lossy() == name)
+        .map(|(name, data)| {
+            Box::into_raw(Box::new(Handle {
+                name: name.clone(),
+                data: data.clone(),
+            }))
+        })
+        .unwrap_or(std::ptr::null_mut())
+}
+
+/// 与 paddle 相同，无论是否创建成功都会释放`pd_config`
+#[no_mangle]
+pub unsafe extern "C" fn PD_PredictorCreate(pd_config: *mut Config) -> *mut Predictor {
+    if pd_config.is_null() {
+        return std::ptr::null_mut();
+    }
+    let config = *Box::from_raw(pd_config);
+    match Predictor::new(config) {
+        Ok(predictor) => Box::into_raw(Box::new(predictor)),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_PredictorClone(pd_predictor: *mut Predictor) -> *mut Predictor {
+    let predictor = &*pd_predictor;
+    let clones = predictor.clones.fetch_add(1, Ordering::Relaxed) + 1;
+    if predictor.model.max_clones.is_some_and(|max| clones > max) {
+        return std::ptr::null_mut();
+    }
+    let mut clone = Predictor::with_model(predictor.config.clone(), predictor.model.clone());
+    clone.clones = predictor.clones.clone();
+    Box::into_raw(Box::new(clone))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_PredictorGetInputNames(
+    pd_predictor: *mut Predictor,
+) -> *mut PD_OneDimArrayCstr {
+    new_array_cstr((*pd_predictor).model.inputs.iter().map(|(name, _)| name))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_PredictorGetOutputNames(
+    pd_predictor: *mut Predictor,
+) -> *mut PD_OneDimArrayCstr {
+    new_array_cstr((*pd_predictor).model.outputs.iter().map(|(name, _)| name))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_PredictorGetInputNum(pd_predictor: *mut Predictor) -> usize {
+    (*pd_predictor).inputs.len()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_PredictorGetOutputNum(pd_predictor: *mut Predictor) -> usize {
+    (*pd_predictor).outputs.len()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_PredictorGetInputHandle(
+    pd_predictor: *mut Predictor,
+    name: *const c_char,
+) -> *mut Handle {
+    handle(&(*pd_predictor).inputs, name)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_PredictorGetOutputHandle(
+    pd_predictor: *mut Predictor,
+    name: *const c_char,
+) -> *mut Handle {
+    handle(&(*pd_predictor).outputs, name)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_PredictorRun(pd_predictor: *mut Predictor) -> PD_Bool {
+    (*pd_predictor).run().is_ok()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_PredictorClearIntermediateTensor(_pd_predictor: *mut Predictor) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_PredictorTryShrinkMemory(pd_predictor: *mut Predictor) -> u64 {
+    let mut pool = (*pd_predictor)
+        .pool
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    std::mem::take(&mut *pool)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_PredictorDestroy(pd_predictor: *mut Predictor) {
+    if !pd_predictor.is_null() {
+        drop(Box::from_raw(pd_predictor));
+    }
+}
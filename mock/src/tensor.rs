@@ -0,0 +1,252 @@
+//! `PD_Tensor*`函数，数据均保存在内存中
+
+use crate::common::*;
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Tensor 数据
+#[derive(Debug, Clone, PartialEq)]
+pub enum Buffer {
+    Float32(Vec<f32>),
+    Int32(Vec<i32>),
+    Int64(Vec<i64>),
+    Uint8(Vec<u8>),
+    Int8(Vec<i8>),
+}
+
+impl Buffer {
+    /// 指定数据类型的空数据，`DataType::Unknown`时返回`None`
+    pub fn empty(data_type: DataType) -> Option<Self> {
+        match data_type {
+            DataType::Unknown => None,
+            DataType::Float32 => Some(Buffer::Float32(vec![])),
+            DataType::Int32 => Some(Buffer::Int32(vec![])),
+            DataType::Int64 => Some(Buffer::Int64(vec![])),
+            DataType::Uint8 => Some(Buffer::Uint8(vec![])),
+            DataType::Int8 => Some(Buffer::Int8(vec![])),
+        }
+    }
+
+    pub fn data_type(&self) -> DataType {
+        match self {
+            Buffer::Float32(_) => DataType::Float32,
+            Buffer::Int32(_) => DataType::Int32,
+            Buffer::Int64(_) => DataType::Int64,
+            Buffer::Uint8(_) => DataType::Uint8,
+            Buffer::Int8(_) => DataType::Int8,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Buffer::Float32(v) => v.len(),
+            Buffer::Int32(v) => v.len(),
+            Buffer::Int64(v) => v.len(),
+            Buffer::Uint8(v) => v.len(),
+            Buffer::Int8(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 数据占用的字节数
+    pub fn bytes(&self) -> usize {
+        match self {
+            Buffer::Float32(v) => std::mem::size_of_val(v.as_slice()),
+            Buffer::Int32(v) => std::mem::size_of_val(v.as_slice()),
+            Buffer::Int64(v) => std::mem::size_of_val(v.as_slice()),
+            Buffer::Uint8(v) => v.len(),
+            Buffer::Int8(v) => v.len(),
+        }
+    }
+}
+
+/// Tensor 的 shape、数据及 LoD
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TensorData {
+    pub shape: Vec<i32>,
+    pub buffer: Option<Buffer>,
+    pub lod: Vec<Vec<usize>>,
+}
+
+impl TensorData {
+    pub fn new(data_type: DataType) -> Self {
+        Self {
+            buffer: Buffer::empty(data_type),
+            ..Default::default()
+        }
+    }
+
+    /// shape 对应的元素数量
+    pub fn size(&self) -> usize {
+        self.shape.iter().map(|&s| s.max(0) as usize).product()
+    }
+
+    pub fn data_type(&self) -> DataType {
+        self.buffer
+            .as_ref()
+            .map(Buffer::data_type)
+            .unwrap_or(DataType::Unknown)
+    }
+
+    /// 是否已经写入了与 shape 匹配的数据
+    pub fn is_ready(&self) -> bool {
+        !self.shape.is_empty() && self.buffer.as_ref().map(Buffer::len) == Some(self.size())
+    }
+}
+
+/// `PD_Tensor`句柄，同一个 Predictor 中同名的句柄共享数据
+pub struct Handle {
+    pub name: CString,
+    pub data: Arc<Mutex<TensorData>>,
+}
+
+impl Handle {
+    fn lock(&self) -> MutexGuard<'_, TensorData> {
+        self.data.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_TensorDestroy(pd_tensor: *mut Handle) {
+    if !pd_tensor.is_null() {
+        drop(Box::from_raw(pd_tensor));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_TensorReshape(
+    pd_tensor: *mut Handle,
+    shape_size: usize,
+    shape: *mut i32,
+) {
+    let shape = if shape.is_null() {
+        vec![]
+    } else {
+        std::slice::from_raw_parts(shape, shape_size).to_vec()
+    };
+    (*pd_tensor).lock().shape = shape;
+}
+
+macro_rules! typed {
+    ($(
+        $ty: ty => $variant: ident {
+            $mutable_data: ident,
+            $data: ident,
+            $copy_from_cpu: ident,
+            $copy_to_cpu: ident
+        }
+    )*) => {
+        $(
+            #[no_mangle]
+            pub unsafe extern "C" fn $mutable_data(pd_tensor: *mut Handle, _place: PlaceType) -> *mut $ty {
+                let mut tensor = (*pd_tensor).lock();
+                let size = tensor.size();
+                if !matches!(&tensor.buffer, Some(Buffer::$variant(v)) if v.len() == size) {
+                    tensor.buffer = Some(Buffer::$variant(vec![<$ty>::default(); size]));
+                }
+                match &mut tensor.buffer {
+                    Some(Buffer::$variant(v)) => v.as_mut_ptr(),
+                    _ => unreachable!(),
+                }
+            }
+
+            #[no_mangle]
+            pub unsafe extern "C" fn $data(
+                pd_tensor: *mut Handle,
+                place: *mut PlaceType,
+                size: *mut i32,
+            ) -> *mut $ty {
+                let mut tensor = (*pd_tensor).lock();
+                match &mut tensor.buffer {
+                    Some(Buffer::$variant(v)) => {
+                        *place = PlaceType::Cpu;
+                        *size = v.len() as i32;
+                        v.as_mut_ptr()
+                    }
+                    _ => {
+                        *place = PlaceType::Unknown;
+                        *size = 0;
+                        std::ptr::null_mut()
+                    }
+                }
+            }
+
+            #[no_mangle]
+            pub unsafe extern "C" fn $copy_from_cpu(pd_tensor: *mut Handle, data: *const $ty) {
+                let mut tensor = (*pd_tensor).lock();
+                let size = tensor.size();
+                tensor.buffer = Some(Buffer::$variant(std::slice::from_raw_parts(data, size).to_vec()));
+            }
+
+            #[no_mangle]
+            pub unsafe extern "C" fn $copy_to_cpu(pd_tensor: *mut Handle, data: *mut $ty) {
+                let tensor = (*pd_tensor).lock();
+                if let Some(Buffer::$variant(v)) = &tensor.buffer {
+                    std::ptr::copy_nonoverlapping(v.as_ptr(), data, v.len().min(tensor.size()));
+                }
+            }
+        )*
+    };
+}
+
+typed! {
+    f32 => Float32 {
+        PD_TensorMutableDataFloat,
+        PD_TensorDataFloat,
+        PD_TensorCopyFromCpuFloat,
+        PD_TensorCopyToCpuFloat
+    }
+    i64 => Int64 {
+        PD_TensorMutableDataInt64,
+        PD_TensorDataInt64,
+        PD_TensorCopyFromCpuInt64,
+        PD_TensorCopyToCpuInt64
+    }
+    i32 => Int32 {
+        PD_TensorMutableDataInt32,
+        PD_TensorDataInt32,
+        PD_TensorCopyFromCpuInt32,
+        PD_TensorCopyToCpuInt32
+    }
+    u8 => Uint8 {
+        PD_TensorMutableDataUint8,
+        PD_TensorDataUint8,
+        PD_TensorCopyFromCpuUint8,
+        PD_TensorCopyToCpuUint8
+    }
+    i8 => Int8 {
+        PD_TensorMutableDataInt8,
+        PD_TensorDataInt8,
+        PD_TensorCopyFromCpuInt8,
+        PD_TensorCopyToCpuInt8
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_TensorGetShape(pd_tensor: *mut Handle) -> *mut PD_OneDimArrayInt32 {
+    new_array_int32((*pd_tensor).lock().shape.clone())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_TensorSetLod(pd_tensor: *mut Handle, lod: *mut PD_TwoDimArraySize) {
+    (*pd_tensor).lock().lod = read_two_dim_array_size(lod);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_TensorGetLod(pd_tensor: *mut Handle) -> *mut PD_TwoDimArraySize {
+    new_two_dim_array_size((*pd_tensor).lock().lod.clone())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_TensorGetName(pd_tensor: *mut Handle) -> *const c_char {
+    (*pd_tensor).name.as_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn PD_TensorGetDataType(pd_tensor: *mut Handle) -> DataType {
+    (*pd_tensor).lock().data_type()
+}
@@ -0,0 +1,69 @@
+//! 服务配置文件
+
+use paddle_inference::config::Config;
+use paddle_inference::ShrinkPolicy;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 服务配置，使用 TOML 格式保存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServeConfig {
+    /// 监听地址，默认为`0.0.0.0:8000`
+    #[serde(default = "default_address")]
+    pub address: String,
+    /// gRPC 接口的监听地址，为`None`时不启动 gRPC 接口
+    #[serde(default)]
+    pub grpc_address: Option<String>,
+    /// REST 请求体及 gRPC 消息的大小上限(字节)，REST 请求超过时返回`413`，默认为`2MB`
+    #[serde(default = "default_body_limit")]
+    pub body_limit: usize,
+    /// `paddle_inference_c`动态库的路径，为`None`时使用默认动态库
+    #[serde(default)]
+    pub library: Option<String>,
+    /// 加载的模型
+    pub models: Vec<ModelConfig>,
+}
+
+/// 单个模型的配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelConfig {
+    /// 模型名称，即 URL 中的`{name}`
+    pub name: String,
+    /// 执行预测的工作线程数量，默认为`1`
+    #[serde(default = "default_workers")]
+    pub workers: usize,
+    /// 等待预测的请求数量上限，超过时返回`503`，默认为`64`，为`0`时按`1`处理
+    #[serde(default = "default_queue")]
+    pub queue: usize,
+    /// 自动释放内存的策略，默认不自动释放
+    #[serde(default)]
+    pub shrink: ShrinkPolicy,
+    /// 创建预测器的配置
+    pub config: Config,
+}
+
+fn default_address() -> String {
+    "0.0.0.0:8000".to_string()
+}
+
+fn default_body_limit() -> usize {
+    2 << 20
+}
+
+fn default_workers() -> usize {
+    1
+}
+
+fn default_queue() -> usize {
+    64
+}
+
+impl ServeConfig {
+    /// 读取并解析配置文件
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("读取配置文件`{}`失败: {}", path.display(), e))?;
+        toml::from_str(&text).map_err(|e| format!("解析配置文件`{}`失败: {}", path.display(), e))
+    }
+}
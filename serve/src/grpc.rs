@@ -0,0 +1,203 @@
+//! KServe v2 gRPC 接口
+//!
+//! 输出数据始终以`raw_output_contents`返回，输入数据可以使用`raw_input_contents`或`contents`
+
+use crate::server::{self, ApiError, Server};
+use crate::v2;
+use axum::http::StatusCode;
+use paddle_inference::common::DataType;
+use paddle_inference::{Error, HostTensor};
+use proto::grpc_inference_service_server::{GrpcInferenceService, GrpcInferenceServiceServer};
+use proto::model_infer_response::InferOutputTensor;
+use proto::model_metadata_response::TensorMetadata;
+use proto::*;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+/// 由`proto/grpc_service.proto`生成的消息及服务
+#[allow(clippy::all)]
+pub mod proto {
+    tonic::include_proto!("inference");
+}
+
+/// gRPC 服务，与 REST 接口共享同一个[`Server`]
+#[derive(Clone)]
+pub struct GrpcService {
+    server: Arc<Server>,
+}
+
+impl GrpcService {
+    pub fn new(server: Arc<Server>) -> Self {
+        Self { server }
+    }
+
+    /// 转换为可以添加到`tonic::transport::Server`的服务，消息大小上限为[`crate::ServeConfig::body_limit`]
+    pub fn into_service(self) -> GrpcInferenceServiceServer<Self> {
+        let body_limit = self.server.body_limit;
+        GrpcInferenceServiceServer::new(self).max_decoding_message_size(body_limit)
+    }
+}
+
+impl From<ApiError> for Status {
+    fn from(e: ApiError) -> Self {
+        match e.status {
+            StatusCode::NOT_FOUND => Status::not_found(e.message),
+            StatusCode::BAD_REQUEST => Status::invalid_argument(e.message),
+            StatusCode::SERVICE_UNAVAILABLE => Status::unavailable(e.message),
+            _ => Status::internal(e.message),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl GrpcInferenceService for GrpcService {
+    async fn server_live(
+        &self,
+        _: Request<ServerLiveRequest>,
+    ) -> Result<Response<ServerLiveResponse>, Status> {
+        Ok(Response::new(ServerLiveResponse { live: true }))
+    }
+
+    /// 所有模型在启动时加载，服务运行后即为就绪状态
+    async fn server_ready(
+        &self,
+        _: Request<ServerReadyRequest>,
+    ) -> Result<Response<ServerReadyResponse>, Status> {
+        Ok(Response::new(ServerReadyResponse { ready: true }))
+    }
+
+    async fn model_ready(
+        &self,
+        request: Request<ModelReadyRequest>,
+    ) -> Result<Response<ModelReadyResponse>, Status> {
+        self.server.metadata(&request.get_ref().name)?;
+        Ok(Response::new(ModelReadyResponse { ready: true }))
+    }
+
+    async fn server_metadata(
+        &self,
+        _: Request<ServerMetadataRequest>,
+    ) -> Result<Response<ServerMetadataResponse>, Status> {
+        let metadata = server::server_metadata();
+        Ok(Response::new(ServerMetadataResponse {
+            name: metadata.name,
+            version: metadata.version,
+            extensions: metadata.extensions,
+        }))
+    }
+
+    async fn model_metadata(
+        &self,
+        request: Request<ModelMetadataRequest>,
+    ) -> Result<Response<ModelMetadataResponse>, Status> {
+        let metadata = self.server.metadata(&request.get_ref().name)?.clone();
+        let tensor = |t: v2::TensorMetadata| TensorMetadata {
+            name: t.name,
+            datatype: t.datatype,
+            shape: t.shape,
+        };
+        Ok(Response::new(ModelMetadataResponse {
+            name: metadata.name,
+            versions: metadata.versions,
+            platform: metadata.platform,
+            inputs: metadata.inputs.into_iter().map(tensor).collect(),
+            outputs: metadata.outputs.into_iter().map(tensor).collect(),
+        }))
+    }
+
+    async fn model_infer(
+        &self,
+        request: Request<ModelInferRequest>,
+    ) -> Result<Response<ModelInferResponse>, Status> {
+        let request = request.into_inner();
+        self.server.metadata(&request.model_name)?;
+
+        // 使用`raw_input_contents`时其数量需与输入数量相同
+        let raw = &request.raw_input_contents;
+        if !raw.is_empty() && raw.len() != request.inputs.len() {
+            return Err(Status::invalid_argument(format!(
+                "`raw_input_contents`的数量{}与输入数量{}不相等",
+                raw.len(),
+                request.inputs.len()
+            )));
+        }
+        let mut inputs = vec![];
+        for (i, input) in request.inputs.iter().enumerate() {
+            let data_type = v2::parse_datatype(&input.datatype).ok_or_else(|| {
+                Status::invalid_argument(format!("不支持的数据类型`{}`", input.datatype))
+            })?;
+            let tensor = match raw.get(i) {
+                Some(bytes) => v2::decode_binary(&input.name, &input.shape, data_type, bytes),
+                None => decode_contents(input, data_type),
+            }
+            .map_err(Status::invalid_argument)?;
+            inputs.push((input.name.clone(), tensor));
+        }
+
+        let mut outputs = self.server.infer(&request.model_name, inputs).await?;
+        if !request.outputs.is_empty() {
+            // 同一个输出可以被请求多次，按名称查找并克隆
+            let all = outputs.into_iter().collect::<BTreeMap<_, _>>();
+            outputs = request
+                .outputs
+                .iter()
+                .map(|o| {
+                    let tensor = all
+                        .get(&o.name)
+                        .ok_or_else(|| ApiError::from(Error::TensorNotFound(o.name.clone())))?;
+                    Ok((o.name.clone(), tensor.clone()))
+                })
+                .collect::<Result<_, ApiError>>()?;
+        }
+
+        let mut response = ModelInferResponse {
+            model_name: request.model_name,
+            model_version: request.model_version,
+            id: request.id,
+            ..Default::default()
+        };
+        for (name, tensor) in outputs {
+            response.outputs.push(InferOutputTensor {
+                name,
+                datatype: v2::datatype_name(tensor.data_type()).to_string(),
+                shape: tensor.shape().iter().map(|&d| d as i64).collect(),
+                ..Default::default()
+            });
+            response
+                .raw_output_contents
+                .push(v2::encode_binary(tensor.data()));
+        }
+        Ok(Response::new(response))
+    }
+}
+
+/// 解码`contents`中的数据，`INT8`及`UINT8`分别使用`int_contents`及`uint_contents`
+fn decode_contents(
+    input: &model_infer_request::InferInputTensor,
+    data_type: DataType,
+) -> Result<HostTensor, String> {
+    fn narrow<S: Copy, T: TryFrom<S>>(name: &str, values: &[S]) -> Result<Vec<T>, String> {
+        values
+            .iter()
+            .map(|&v| T::try_from(v).ok())
+            .collect::<Option<_>>()
+            .ok_or_else(|| format!("输入`{}`的数据超出类型范围", name))
+    }
+
+    let name = &input.name;
+    let contents = input
+        .contents
+        .as_ref()
+        .ok_or_else(|| format!("输入`{}`缺少数据", name))?;
+    let shape = v2::tensor_shape(name, &input.shape)?;
+    match data_type {
+        DataType::Float32 => HostTensor::new(shape, contents.fp32_contents.clone()),
+        DataType::Int64 => HostTensor::new(shape, contents.int64_contents.clone()),
+        DataType::Int32 => HostTensor::new(shape, contents.int_contents.clone()),
+        DataType::Uint8 => HostTensor::new(shape, narrow::<_, u8>(name, &contents.uint_contents)?),
+        DataType::Int8 => HostTensor::new(shape, narrow::<_, i8>(name, &contents.int_contents)?),
+        DataType::Unknown => return Err("数据类型未知".to_string()),
+    }
+    .map_err(|e| format!("输入`{}`: {}", name, e))
+}
@@ -0,0 +1,22 @@
+//! 基于`paddle_inference`的推理服务，实现 KServe v2 REST 及 gRPC 协议
+//!
+//! 支持的接口：
+//! - `GET /v2`：服务元数据
+//! - `GET /v2/health/live`、`GET /v2/health/ready`：服务状态
+//! - `GET /v2/models/{name}`、`GET /v2/models/{name}/ready`：模型元数据及状态
+//! - `POST /v2/models/{name}/infer`：执行预测，支持 JSON 数据及二进制 Tensor 扩展
+//! - `GET /metrics`：Prometheus 文本格式的预测指标，见`paddle_inference::metrics`
+//!
+//! 设置[`ServeConfig::grpc_address`]时同时启动 gRPC 接口，支持`ServerLive`、`ServerReady`、`ModelReady`、
+//! `ServerMetadata`、`ModelMetadata`及`ModelInfer`，定义见`proto/grpc_service.proto`
+//!
+//! 支持的数据类型为`FP32`、`INT64`、`INT32`、`UINT8`、`INT8`
+
+pub mod config;
+pub mod grpc;
+pub mod server;
+pub mod v2;
+
+pub use config::{ModelConfig, ServeConfig};
+pub use grpc::GrpcService;
+pub use server::Server;
@@ -0,0 +1,45 @@
+use clap::Parser;
+use paddle_serve::{ServeConfig, Server};
+use std::error::Error;
+use std::sync::Arc;
+
+/// KServe v2 推理服务
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Args {
+    /// 配置文件路径
+    #[arg(short, long, default_value = "paddle-serve.toml")]
+    config: String,
+    /// 请求体大小上限(字节)，覆盖配置文件中的`body_limit`
+    #[arg(long)]
+    body_limit: Option<usize>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+    let mut config = ServeConfig::load(&args.config)?;
+    if let Some(body_limit) = args.body_limit {
+        config.body_limit = body_limit;
+    }
+    let server = Arc::new(Server::load(&config)?);
+
+    if let Some(address) = &config.grpc_address {
+        let address = address.parse()?;
+        let grpc = tonic::transport::Server::builder()
+            .add_service(server.clone().grpc())
+            .serve(address);
+        println!("paddle-serve gRPC 监听于 {}", address);
+        tokio::spawn(async move {
+            if let Err(e) = grpc.await {
+                eprintln!("gRPC 服务错误: {}", e);
+                std::process::exit(1);
+            }
+        });
+    }
+
+    let listener = tokio::net::TcpListener::bind(&config.address).await?;
+    println!("paddle-serve 监听于 {}", config.address);
+    axum::serve(listener, server.router()).await?;
+    Ok(())
+}
@@ -0,0 +1,316 @@
+//! KServe v2 REST 接口
+
+use crate::config::ServeConfig;
+use crate::grpc::proto::grpc_inference_service_server::GrpcInferenceServiceServer;
+use crate::grpc::GrpcService;
+use crate::v2::{
+    self, InferRequest, InferResponse, ModelMetadata, ResponseOutput, ServerMetadata,
+    TensorMetadata, HEADER_CONTENT_LENGTH,
+};
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use paddle_inference::{AsyncPredictor, Error, NamedTensors, Predictor, Runtime};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// 已加载的模型
+struct LoadedModel {
+    predictor: AsyncPredictor,
+    metadata: ModelMetadata,
+}
+
+/// 推理服务，包含所有已加载的模型
+pub struct Server {
+    models: BTreeMap<String, LoadedModel>,
+    /// 见[`ServeConfig::body_limit`]
+    pub(crate) body_limit: usize,
+}
+
+impl Server {
+    /// 加载`config`中的所有模型
+    pub fn load(config: &ServeConfig) -> paddle_inference::Result<Self> {
+        let runtime = config.library.as_deref().map(Runtime::load).transpose()?;
+        let mut models = BTreeMap::new();
+        for model in &config.models {
+            let mut predictor_config = model.config.clone();
+            if predictor_config.model_name.is_none() {
+                predictor_config = predictor_config.model_name(&model.name);
+            }
+            if let Some(runtime) = &runtime {
+                predictor_config = predictor_config.runtime(runtime.clone());
+            }
+            let mut predictor = predictor_config.build()?;
+            predictor.set_shrink_policy(model.shrink.clone());
+            let metadata = metadata(&model.name, &mut predictor)?;
+            let predictor = AsyncPredictor::from_predictor(predictor, model.workers, model.queue)?;
+            models.insert(
+                model.name.clone(),
+                LoadedModel {
+                    predictor,
+                    metadata,
+                },
+            );
+        }
+        Ok(Self {
+            models,
+            body_limit: config.body_limit,
+        })
+    }
+
+    /// 创建包含所有 v2 REST 接口的路由
+    pub fn router(self: Arc<Self>) -> Router {
+        let body_limit = self.body_limit;
+        Router::new()
+            .route("/v2", get(|| async { Json(server_metadata()) }))
+            .route("/v2/health/live", get(health))
+            .route("/v2/health/ready", get(health))
+            .route("/v2/models/:name", get(model_metadata))
+            .route("/v2/models/:name/ready", get(model_ready))
+            .route("/v2/models/:name/infer", post(infer))
+            .route(
+                "/metrics",
+                get(|| async { paddle_inference::metrics::gather() }),
+            )
+            .layer(DefaultBodyLimit::max(body_limit))
+            .with_state(self)
+    }
+
+    /// 创建 v2 gRPC 服务
+    pub fn grpc(self: Arc<Self>) -> GrpcInferenceServiceServer<GrpcService> {
+        GrpcService::new(self).into_service()
+    }
+
+    fn model(&self, name: &str) -> Result<&LoadedModel, ApiError> {
+        self.models
+            .get(name)
+            .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, format!("模型`{}`不存在", name)))
+    }
+
+    /// 模型`name`的元数据
+    pub(crate) fn metadata(&self, name: &str) -> Result<&ModelMetadata, ApiError> {
+        Ok(&self.model(name)?.metadata)
+    }
+
+    /// 使用模型`name`执行预测
+    pub(crate) async fn infer(
+        &self,
+        name: &str,
+        inputs: NamedTensors,
+    ) -> Result<NamedTensors, ApiError> {
+        Ok(self.model(name)?.predictor.run(inputs).await?)
+    }
+}
+
+fn metadata(name: &str, predictor: &mut Predictor) -> paddle_inference::Result<ModelMetadata> {
+    let tensor = |tensor: paddle_inference::Tensor| TensorMetadata {
+        name: tensor.name().into_owned(),
+        datatype: v2::datatype_name(tensor.data_type()).to_string(),
+        shape: tensor.shape().into_iter().map(i64::from).collect(),
+    };
+    let inputs: Vec<String> = predictor.input_names().into();
+    let outputs: Vec<String> = predictor.output_names().into();
+    Ok(ModelMetadata {
+        name: name.to_string(),
+        versions: vec![],
+        platform: "paddle_inference".to_string(),
+        inputs: inputs
+            .iter()
+            .map(|n| predictor.input(n).map(tensor))
+            .collect::<Result<_, _>>()?,
+        outputs: outputs
+            .iter()
+            .map(|n| predictor.output(n).map(tensor))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// v2 协议的错误响应
+#[derive(Debug)]
+pub struct ApiError {
+    pub(crate) status: StatusCode,
+    pub(crate) message: String,
+}
+
+impl ApiError {
+    pub(crate) fn new(status: StatusCode, message: String) -> Self {
+        Self { status, message }
+    }
+
+    pub(crate) fn bad_request(message: String) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        let status = match e {
+            Error::QueueFull => StatusCode::SERVICE_UNAVAILABLE,
+            Error::TensorNotFound(_)
+            | Error::ShapeMismatch { .. }
+            | Error::DataTypeMismatch { .. } => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self::new(status, e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
+
+/// 服务元数据，REST 及 gRPC 接口共用
+pub(crate) fn server_metadata() -> ServerMetadata {
+    ServerMetadata {
+        name: env!("CARGO_PKG_NAME").to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        extensions: vec!["binary_tensor_data".to_string()],
+    }
+}
+
+/// 所有模型在启动时加载，服务运行后即为就绪状态
+async fn health() -> StatusCode {
+    StatusCode::OK
+}
+
+async fn model_metadata(
+    State(server): State<Arc<Server>>,
+    Path(name): Path<String>,
+) -> Result<Json<ModelMetadata>, ApiError> {
+    Ok(Json(server.metadata(&name)?.clone()))
+}
+
+async fn model_ready(
+    State(server): State<Arc<Server>>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    server.model(&name)?;
+    Ok(StatusCode::OK)
+}
+
+async fn infer(
+    State(server): State<Arc<Server>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ApiError> {
+    server.model(&name)?;
+
+    // 二进制扩展：请求体由 JSON 及其后的二进制数据组成
+    let json_len = match headers.get(HEADER_CONTENT_LENGTH) {
+        Some(value) => value
+            .to_str()
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|&len| len <= body.len())
+            .ok_or_else(|| ApiError::bad_request(format!("`{}`无效", HEADER_CONTENT_LENGTH)))?,
+        None => body.len(),
+    };
+    let request: InferRequest = serde_json::from_slice(&body[..json_len])
+        .map_err(|e| ApiError::bad_request(format!("解析请求失败: {}", e)))?;
+
+    let mut binary = &body[json_len..];
+    let mut inputs = vec![];
+    for input in &request.inputs {
+        let data_type = v2::parse_datatype(&input.datatype).ok_or_else(|| {
+            ApiError::bad_request(format!("不支持的数据类型`{}`", input.datatype))
+        })?;
+        let tensor = match v2::binary_data_size(&input.parameters) {
+            Some(size) => {
+                if size > binary.len() {
+                    return Err(ApiError::bad_request(format!(
+                        "输入`{}`的二进制数据不完整",
+                        input.name
+                    )));
+                }
+                let (data, rest) = binary.split_at(size);
+                binary = rest;
+                v2::decode_binary(&input.name, &input.shape, data_type, data)
+            }
+            None => v2::decode_json(input, data_type),
+        }
+        .map_err(ApiError::bad_request)?;
+        inputs.push((input.name.clone(), tensor));
+    }
+
+    let outputs = server.infer(&name, inputs).await?;
+
+    let binary_output = v2::bool_parameter(&request.parameters, "binary_data_output");
+    let requested = if request.outputs.is_empty() {
+        outputs
+            .iter()
+            .map(|(name, _)| (name.clone(), binary_output))
+            .collect::<Vec<_>>()
+    } else {
+        request
+            .outputs
+            .iter()
+            .map(|o| {
+                let binary = o
+                    .parameters
+                    .get("binary_data")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(binary_output);
+                (o.name.clone(), binary)
+            })
+            .collect()
+    };
+
+    // 同一个输出可以被请求多次，按名称查找而不是取出
+    let outputs = outputs.into_iter().collect::<BTreeMap<_, _>>();
+    let mut response = InferResponse {
+        model_name: name,
+        id: request.id,
+        outputs: vec![],
+    };
+    let mut binary_data = vec![];
+    let mut has_binary = false;
+    for (name, binary) in requested {
+        let tensor = outputs
+            .get(&name)
+            .ok_or_else(|| ApiError::from(Error::TensorNotFound(name.clone())))?;
+        let mut output = ResponseOutput {
+            name,
+            shape: tensor.shape().to_vec(),
+            datatype: v2::datatype_name(tensor.data_type()).to_string(),
+            parameters: Default::default(),
+            data: None,
+        };
+        if binary {
+            has_binary = true;
+            let bytes = v2::encode_binary(tensor.data());
+            output
+                .parameters
+                .insert("binary_data_size".to_string(), bytes.len().into());
+            binary_data.extend(bytes);
+        } else {
+            output.data = Some(v2::encode_json(tensor.data()));
+        }
+        response.outputs.push(output);
+    }
+
+    let json = serde_json::to_vec(&response).unwrap();
+    if !has_binary {
+        return Ok(([(header::CONTENT_TYPE, "application/json")], json).into_response());
+    }
+    let json_len = json.len();
+    let mut body = json;
+    body.extend(binary_data);
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+            (
+                header::HeaderName::from_static("inference-header-content-length"),
+                json_len.to_string(),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
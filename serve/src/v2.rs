@@ -0,0 +1,298 @@
+//! KServe v2 推理协议的数据结构及 Tensor 编解码
+//!
+//! 参考 [KServe v2 协议](https://kserve.github.io/website/latest/modelserving/data_plane/v2_protocol/)
+//! 及 [Triton 二进制 Tensor 扩展](https://github.com/triton-inference-server/server/blob/main/docs/protocol/extension_binary_data.md)
+
+use paddle_inference::common::DataType;
+use paddle_inference::{Element, HostData, HostTensor};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// 请求及响应中的`parameters`
+pub type Parameters = Map<String, Value>;
+
+/// 请求体中 JSON 部分的长度，其余部分为二进制 Tensor 数据
+pub const HEADER_CONTENT_LENGTH: &str = "Inference-Header-Content-Length";
+
+/// `GET /v2`的响应
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerMetadata {
+    pub name: String,
+    pub version: String,
+    pub extensions: Vec<String>,
+}
+
+/// 输入/输出 Tensor 的元数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TensorMetadata {
+    pub name: String,
+    pub datatype: String,
+    /// 未知的维度为`-1`
+    pub shape: Vec<i64>,
+}
+
+/// `GET /v2/models/{name}`的响应
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelMetadata {
+    pub name: String,
+    pub versions: Vec<String>,
+    pub platform: String,
+    pub inputs: Vec<TensorMetadata>,
+    pub outputs: Vec<TensorMetadata>,
+}
+
+/// `POST /v2/models/{name}/infer`的请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InferRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub parameters: Parameters,
+    pub inputs: Vec<RequestInput>,
+    /// 需要返回的输出，为空时返回所有输出
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<RequestOutput>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestInput {
+    pub name: String,
+    pub shape: Vec<i64>,
+    pub datatype: String,
+    /// 包含`binary_data_size`时数据位于请求体的二进制部分
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub parameters: Parameters,
+    /// 数据，可以为一维或与`shape`对应的嵌套数组
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestOutput {
+    pub name: String,
+    /// `binary_data`为`true`时以二进制返回数据
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub parameters: Parameters,
+}
+
+/// `POST /v2/models/{name}/infer`的响应
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InferResponse {
+    pub model_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub outputs: Vec<ResponseOutput>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseOutput {
+    pub name: String,
+    pub shape: Vec<usize>,
+    pub datatype: String,
+    /// 以二进制返回时包含`binary_data_size`
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub parameters: Parameters,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<Value>>,
+}
+
+/// [`DataType`]对应的 v2 数据类型名称
+pub fn datatype_name(data_type: DataType) -> &'static str {
+    match data_type {
+        DataType::Float32 => "FP32",
+        DataType::Int64 => "INT64",
+        DataType::Int32 => "INT32",
+        DataType::Uint8 => "UINT8",
+        DataType::Int8 => "INT8",
+        DataType::Unknown => "UNKNOWN",
+    }
+}
+
+/// 解析 v2 数据类型名称，不支持的类型返回`None`
+pub fn parse_datatype(name: &str) -> Option<DataType> {
+    match name {
+        "FP32" => Some(DataType::Float32),
+        "INT64" => Some(DataType::Int64),
+        "INT32" => Some(DataType::Int32),
+        "UINT8" => Some(DataType::Uint8),
+        "INT8" => Some(DataType::Int8),
+        _ => None,
+    }
+}
+
+/// v2 协议中数据的编解码
+trait Wire: Element {
+    const SIZE: usize;
+    fn from_le(bytes: &[u8]) -> Self;
+    fn write_le(self, out: &mut Vec<u8>);
+    fn from_json(value: &Value) -> Option<Self>;
+    fn to_json(self) -> Value;
+}
+
+macro_rules! wire_int {
+    ($( $ty: ty ),*) => {
+        $(
+            impl Wire for $ty {
+                const SIZE: usize = std::mem::size_of::<$ty>();
+
+                fn from_le(bytes: &[u8]) -> Self {
+                    <$ty>::from_le_bytes(bytes.try_into().unwrap())
+                }
+
+                fn write_le(self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn from_json(value: &Value) -> Option<Self> {
+                    value.as_i64().and_then(|v| <$ty>::try_from(v).ok())
+                }
+
+                fn to_json(self) -> Value {
+                    Value::from(self)
+                }
+            }
+        )*
+    };
+}
+
+wire_int!(i64, i32, u8, i8);
+
+impl Wire for f32 {
+    const SIZE: usize = 4;
+
+    fn from_le(bytes: &[u8]) -> Self {
+        f32::from_le_bytes(bytes.try_into().unwrap())
+    }
+
+    fn write_le(self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        value.as_f64().map(|v| v as f32)
+    }
+
+    /// `NaN`及无穷大转换为`null`
+    fn to_json(self) -> Value {
+        Value::from(self as f64)
+    }
+}
+
+macro_rules! dispatch {
+    ($data_type: expr, $f: ident ( $( $args: expr ),* )) => {
+        match $data_type {
+            DataType::Float32 => $f::<f32>($( $args ),*),
+            DataType::Int64 => $f::<i64>($( $args ),*),
+            DataType::Int32 => $f::<i32>($( $args ),*),
+            DataType::Uint8 => $f::<u8>($( $args ),*),
+            DataType::Int8 => $f::<i8>($( $args ),*),
+            DataType::Unknown => Err("数据类型未知".to_string()),
+        }
+    };
+}
+
+macro_rules! each {
+    ($data: expr, $v: ident => $e: expr) => {
+        match $data {
+            HostData::Float32($v) => $e,
+            HostData::Int64($v) => $e,
+            HostData::Int32($v) => $e,
+            HostData::Uint8($v) => $e,
+            HostData::Int8($v) => $e,
+        }
+    };
+}
+
+/// 检查输入`name`的 shape，包含负数、超过`i32`范围的维度或元素数量溢出时返回错误
+pub fn tensor_shape(name: &str, shape: &[i64]) -> Result<Vec<usize>, String> {
+    let error = || format!("输入`{}`的shape {:?}无效", name, shape);
+    let shape = shape
+        .iter()
+        .map(|&d| i32::try_from(d).ok().and_then(|d| usize::try_from(d).ok()))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(error)?;
+    shape
+        .iter()
+        .try_fold(1usize, |s, &d| s.checked_mul(d))
+        .ok_or_else(error)?;
+    Ok(shape)
+}
+
+/// 解码`data`中的 JSON 数据
+pub fn decode_json(input: &RequestInput, data_type: DataType) -> Result<HostTensor, String> {
+    fn decode<T: Wire>(input: &RequestInput, data: &Value) -> Result<HostTensor, String> {
+        let mut values = vec![];
+        flatten::<T>(data, &mut values)
+            .ok_or_else(|| format!("输入`{}`的数据与类型{}不匹配", input.name, input.datatype))?;
+        let shape = tensor_shape(&input.name, &input.shape)?;
+        HostTensor::new(shape, values).map_err(|e| format!("输入`{}`: {}", input.name, e))
+    }
+
+    let data = input
+        .data
+        .as_ref()
+        .ok_or_else(|| format!("输入`{}`缺少数据", input.name))?;
+    dispatch!(data_type, decode(input, data))
+}
+
+fn flatten<T: Wire>(value: &Value, out: &mut Vec<T>) -> Option<()> {
+    match value {
+        Value::Array(values) => values.iter().try_for_each(|v| flatten(v, out)),
+        v => {
+            out.push(T::from_json(v)?);
+            Some(())
+        }
+    }
+}
+
+/// 解码二进制数据，数据为小端序
+pub fn decode_binary(
+    name: &str,
+    shape: &[i64],
+    data_type: DataType,
+    bytes: &[u8],
+) -> Result<HostTensor, String> {
+    fn decode<T: Wire>(name: &str, shape: &[i64], bytes: &[u8]) -> Result<HostTensor, String> {
+        if !bytes.len().is_multiple_of(T::SIZE) {
+            return Err(format!(
+                "输入`{}`的二进制数据长度{}不是{}的倍数",
+                name,
+                bytes.len(),
+                T::SIZE
+            ));
+        }
+        let values = bytes.chunks_exact(T::SIZE).map(T::from_le).collect();
+        HostTensor::new(tensor_shape(name, shape)?, values)
+            .map_err(|e| format!("输入`{}`: {}", name, e))
+    }
+
+    dispatch!(data_type, decode(name, shape, bytes))
+}
+
+/// 编码为 JSON 数据
+pub fn encode_json(data: &HostData) -> Vec<Value> {
+    each!(data, v => v.iter().map(|x| x.to_json()).collect())
+}
+
+/// 编码为小端序的二进制数据
+pub fn encode_binary(data: &HostData) -> Vec<u8> {
+    let mut out = vec![];
+    each!(data, v => v.iter().for_each(|x| x.write_le(&mut out)));
+    out
+}
+
+/// 读取`parameters`中的布尔值
+pub fn bool_parameter(parameters: &Parameters, key: &str) -> bool {
+    parameters
+        .get(key)
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// 读取`parameters`中的`binary_data_size`
+pub fn binary_data_size(parameters: &Parameters) -> Option<usize> {
+    parameters
+        .get("binary_data_size")
+        .and_then(Value::as_u64)
+        .map(|s| s as usize)
+}
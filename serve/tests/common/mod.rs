@@ -0,0 +1,40 @@
+//! 集成测试共用的函数
+
+use paddle_inference::config::model::Model;
+use paddle_inference::config::Config;
+use paddle_inference::{Runtime, ShrinkPolicy};
+use paddle_serve::{ModelConfig, ServeConfig};
+use std::time::Duration;
+
+const ADD_MODEL: &str = "
+input x float32
+input y float32
+output sum = add x y
+output copy = identity x
+";
+
+/// 使用模拟动态库的配置
+pub fn serve_config() -> ServeConfig {
+    let exe = std::env::current_exe().unwrap();
+    let dir = exe.parent().unwrap();
+    let runtime = Runtime::search([dir, dir.parent().unwrap()]).unwrap();
+    ServeConfig {
+        address: "127.0.0.1:0".to_string(),
+        grpc_address: None,
+        body_limit: 2 << 20,
+        library: Some(runtime.path().display().to_string()),
+        models: vec![ModelConfig {
+            name: "add".to_string(),
+            workers: 2,
+            queue: 8,
+            shrink: ShrinkPolicy {
+                input_bytes: Some(1 << 20),
+                idle: Some(Duration::from_secs(1)),
+            },
+            config: Config::new(Model::Memory {
+                model: ADD_MODEL.as_bytes().to_vec(),
+                params: vec![],
+            }),
+        }],
+    }
+}
@@ -0,0 +1,212 @@
+mod common;
+
+use common::serve_config;
+use paddle_serve::grpc::proto::grpc_inference_service_client::GrpcInferenceServiceClient;
+use paddle_serve::grpc::proto::model_infer_request::{
+    InferInputTensor, InferRequestedOutputTensor,
+};
+use paddle_serve::grpc::proto::*;
+use paddle_serve::Server;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Channel;
+use tonic::Code;
+
+/// 在本地随机端口启动 gRPC 服务并连接
+async fn client() -> GrpcInferenceServiceClient<Channel> {
+    let server = Arc::new(Server::load(&serve_config()).unwrap());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(server.grpc())
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    GrpcInferenceServiceClient::connect(format!("http://{}", address))
+        .await
+        .unwrap()
+}
+
+fn input(name: &str, shape: Vec<i64>) -> InferInputTensor {
+    InferInputTensor {
+        name: name.to_string(),
+        datatype: "FP32".to_string(),
+        shape,
+        ..Default::default()
+    }
+}
+
+fn raw(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+#[tokio::test]
+async fn test_metadata() {
+    let mut client = client().await;
+    assert!(
+        client
+            .server_live(ServerLiveRequest {})
+            .await
+            .unwrap()
+            .into_inner()
+            .live
+    );
+    assert!(
+        client
+            .server_ready(ServerReadyRequest {})
+            .await
+            .unwrap()
+            .into_inner()
+            .ready
+    );
+    let metadata = client
+        .server_metadata(ServerMetadataRequest {})
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(metadata.name, "paddle-serve");
+
+    let ready = ModelReadyRequest {
+        name: "add".to_string(),
+        version: String::new(),
+    };
+    assert!(client.model_ready(ready).await.unwrap().into_inner().ready);
+
+    let metadata = client
+        .model_metadata(ModelMetadataRequest {
+            name: "add".to_string(),
+            version: String::new(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(metadata.inputs[0].name, "x");
+    assert_eq!(metadata.inputs[0].datatype, "FP32");
+    assert_eq!(metadata.outputs[0].name, "sum");
+
+    let status = client
+        .model_metadata(ModelMetadataRequest {
+            name: "none".to_string(),
+            version: String::new(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn test_infer_raw() {
+    let mut client = client().await;
+    let response = client
+        .model_infer(ModelInferRequest {
+            model_name: "add".to_string(),
+            id: "1".to_string(),
+            inputs: vec![input("x", vec![2, 2]), input("y", vec![2, 2])],
+            outputs: vec![InferRequestedOutputTensor {
+                name: "sum".to_string(),
+                ..Default::default()
+            }],
+            raw_input_contents: vec![raw(&[1.0, 2.0, 3.0, 4.0]), raw(&[10.0, 20.0, 30.0, 40.0])],
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.model_name, "add");
+    assert_eq!(response.id, "1");
+    assert_eq!(response.outputs.len(), 1);
+    assert_eq!(response.outputs[0].name, "sum");
+    assert_eq!(response.outputs[0].shape, [2, 2]);
+    assert_eq!(
+        response.raw_output_contents,
+        [raw(&[11.0, 22.0, 33.0, 44.0])]
+    );
+
+    let status = client
+        .model_infer(ModelInferRequest {
+            model_name: "add".to_string(),
+            inputs: vec![input("x", vec![3])],
+            raw_input_contents: vec![raw(&[1.0])],
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn test_infer_contents() {
+    let mut client = client().await;
+    let contents = |values: Vec<f32>| InferTensorContents {
+        fp32_contents: values,
+        ..Default::default()
+    };
+    let response = client
+        .model_infer(ModelInferRequest {
+            model_name: "add".to_string(),
+            inputs: vec![
+                InferInputTensor {
+                    contents: Some(contents(vec![1.5, 2.5])),
+                    ..input("x", vec![2])
+                },
+                InferInputTensor {
+                    contents: Some(contents(vec![1.0, 1.0])),
+                    ..input("y", vec![2])
+                },
+            ],
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    let names = response
+        .outputs
+        .iter()
+        .map(|o| &o.name[..])
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["sum", "copy"]);
+    assert_eq!(
+        response.raw_output_contents,
+        [raw(&[2.5, 3.5]), raw(&[1.5, 2.5])]
+    );
+
+    let status = client
+        .model_infer(ModelInferRequest {
+            model_name: "none".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn test_duplicated_outputs() {
+    let mut client = client().await;
+    let output = |name: &str| InferRequestedOutputTensor {
+        name: name.to_string(),
+        ..Default::default()
+    };
+    let response = client
+        .model_infer(ModelInferRequest {
+            model_name: "add".to_string(),
+            inputs: vec![input("x", vec![1]), input("y", vec![1])],
+            outputs: vec![output("copy"), output("sum"), output("copy")],
+            raw_input_contents: vec![raw(&[1.0]), raw(&[2.0])],
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    let names = response
+        .outputs
+        .iter()
+        .map(|o| &o.name[..])
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["copy", "sum", "copy"]);
+    assert_eq!(
+        response.raw_output_contents,
+        [raw(&[1.0]), raw(&[3.0]), raw(&[1.0])]
+    );
+}
@@ -0,0 +1,248 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::Router;
+use common::serve_config;
+use http_body_util::BodyExt;
+use paddle_serve::{ServeConfig, Server};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+fn router() -> Router {
+    router_with(&serve_config())
+}
+
+fn router_with(config: &ServeConfig) -> Router {
+    Arc::new(Server::load(config).unwrap()).router()
+}
+
+async fn send(request: Request<Body>) -> (StatusCode, Vec<u8>) {
+    let response = router().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, body.to_vec())
+}
+
+async fn get(uri: &str) -> (StatusCode, Value) {
+    let (status, body) = send(Request::get(uri).body(Body::empty()).unwrap()).await;
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn infer(body: Value) -> (StatusCode, Value) {
+    let request = Request::post("/v2/models/add/infer")
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let (status, body) = send(request).await;
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[test]
+fn test_config() {
+    let config = serve_config();
+    let text = toml::to_string(&config).unwrap();
+    let parsed: ServeConfig = toml::from_str(&text).unwrap();
+    assert_eq!(parsed.models[0].name, "add");
+    assert_eq!(parsed.library, config.library);
+    assert_eq!(parsed.models[0].shrink, config.models[0].shrink);
+
+    let example: ServeConfig =
+        toml::from_str(include_str!("../paddle-serve.example.toml")).unwrap();
+    assert_eq!(example.address, "0.0.0.0:8000");
+    assert_eq!(example.grpc_address.as_deref(), Some("0.0.0.0:8001"));
+    assert_eq!(example.body_limit, 2 << 20);
+    assert_eq!(example.models[0].workers, 2);
+    assert_eq!(
+        example.models[0].shrink.idle,
+        Some(std::time::Duration::from_secs(60))
+    );
+}
+
+#[tokio::test]
+async fn test_metadata() {
+    assert_eq!(get("/v2/health/live").await.0, StatusCode::OK);
+    assert_eq!(get("/v2/health/ready").await.0, StatusCode::OK);
+    assert_eq!(
+        get("/v2").await.1["extensions"],
+        json!(["binary_tensor_data"])
+    );
+    assert_eq!(get("/v2/models/add/ready").await.0, StatusCode::OK);
+    assert_eq!(get("/v2/models/none").await.0, StatusCode::NOT_FOUND);
+
+    let (status, metadata) = get("/v2/models/add").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(metadata["inputs"][0]["name"], "x");
+    assert_eq!(metadata["inputs"][0]["datatype"], "FP32");
+    assert_eq!(metadata["outputs"][0]["name"], "sum");
+}
+
+#[tokio::test]
+async fn test_metrics() {
+    let (status, _) = infer(json!({
+        "inputs": [
+            {"name": "x", "shape": [1], "datatype": "FP32", "data": [1]},
+            {"name": "y", "shape": [1], "datatype": "FP32", "data": [2]}
+        ]
+    }))
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send(Request::get("/metrics").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+    let text = String::from_utf8(body).unwrap();
+    assert!(
+        text.contains(r#"paddle_inference_runs_total{model="add"}"#),
+        "{}",
+        text
+    );
+}
+
+#[tokio::test]
+async fn test_infer_json() {
+    let (status, response) = infer(json!({
+        "id": "1",
+        "inputs": [
+            {"name": "x", "shape": [2, 2], "datatype": "FP32", "data": [[1, 2], [3, 4]]},
+            {"name": "y", "shape": [2, 2], "datatype": "FP32", "data": [10, 20, 30, 40]}
+        ],
+        "outputs": [{"name": "sum"}]
+    }))
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        response,
+        json!({
+            "model_name": "add",
+            "id": "1",
+            "outputs": [
+                {"name": "sum", "shape": [2, 2], "datatype": "FP32", "data": [11.0, 22.0, 33.0, 44.0]}
+            ]
+        })
+    );
+
+    let (status, response) = infer(json!({
+        "inputs": [{"name": "x", "shape": [1], "datatype": "BOOL", "data": [true]}]
+    }))
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(response["error"].as_str().unwrap().contains("BOOL"));
+
+    let (status, _) = infer(json!({
+        "inputs": [{"name": "x", "shape": [2], "datatype": "FP32", "data": [1]}]
+    }))
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // 元素数量溢出
+    let (status, response) = infer(json!({
+        "inputs": [{"name": "x", "shape": [2147483647, 2147483647, 2147483647, 0], "datatype": "FP32", "data": []}]
+    }))
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(response["error"].as_str().unwrap().contains("shape"));
+}
+
+#[tokio::test]
+async fn test_infer_binary() {
+    let header = json!({
+        "inputs": [
+            {"name": "x", "shape": [2], "datatype": "FP32", "parameters": {"binary_data_size": 8}},
+            {"name": "y", "shape": [2], "datatype": "FP32", "data": [1, 1]}
+        ],
+        "outputs": [{"name": "sum", "parameters": {"binary_data": true}}, {"name": "copy"}]
+    })
+    .to_string();
+    let mut body = header.clone().into_bytes();
+    for v in [1.5f32, 2.5] {
+        body.extend(v.to_le_bytes());
+    }
+    let request = Request::post("/v2/models/add/infer")
+        .header("Inference-Header-Content-Length", header.len())
+        .body(Body::from(body))
+        .unwrap();
+
+    let response = router().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json_len: usize = response.headers()["inference-header-content-length"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let (json, binary) = body.split_at(json_len);
+    let json: Value = serde_json::from_slice(json).unwrap();
+    assert_eq!(json["outputs"][0]["parameters"]["binary_data_size"], 8);
+    assert_eq!(json["outputs"][1]["data"], json!([1.5, 2.5]));
+    let sum = binary
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(sum, [2.5, 3.5]);
+}
+
+#[tokio::test]
+async fn test_duplicated_outputs() {
+    let (status, response) = infer(json!({
+        "inputs": [
+            {"name": "x", "shape": [1], "datatype": "FP32", "data": [1]},
+            {"name": "y", "shape": [1], "datatype": "FP32", "data": [2]}
+        ],
+        "outputs": [{"name": "copy"}, {"name": "sum"}, {"name": "copy"}]
+    }))
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let names = response["outputs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|o| o["name"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["copy", "sum", "copy"]);
+    assert_eq!(response["outputs"][2]["data"], json!([1.0]));
+}
+
+#[tokio::test]
+async fn test_body_limit() {
+    // 两个各 1MB 的二进制输入，加上 JSON 头后超过默认的 2MB
+    let len = 1 << 18;
+    let request = || {
+        let header = json!({
+            "inputs": [
+                {"name": "x", "shape": [len], "datatype": "FP32", "parameters": {"binary_data_size": len * 4}},
+                {"name": "y", "shape": [len], "datatype": "FP32", "parameters": {"binary_data_size": len * 4}}
+            ],
+            "outputs": [{"name": "sum", "parameters": {"binary_data": true}}]
+        })
+        .to_string();
+        let mut body = header.clone().into_bytes();
+        body.resize(header.len() + len * 8, 0);
+        Request::post("/v2/models/add/infer")
+            .header("Inference-Header-Content-Length", header.len())
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response = router().oneshot(request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    let mut config = serve_config();
+    config.body_limit = 4 << 20;
+    let response = router_with(&config).oneshot(request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_run_failed() {
+    // 输入的 shape 均合法，但模型无法将其相加，属于服务端预测失败
+    let (status, response) = infer(json!({
+        "inputs": [
+            {"name": "x", "shape": [2], "datatype": "FP32", "data": [1, 2]},
+            {"name": "y", "shape": [3], "datatype": "FP32", "data": [1, 2, 3]}
+        ]
+    }))
+    .await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(response["error"].is_string());
+}
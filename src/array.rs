@@ -0,0 +1,48 @@
+//! `ndarray`支持，需要启用`ndarray`特性
+
+use crate::common::PlaceType;
+use crate::tensor::{Element, Tensor};
+use crate::{Error, Result};
+use ndarray::{ArrayD, ArrayView, ArrayViewD, Dimension, IxDyn};
+
+impl Tensor<'_> {
+    /// 按照`array`的 shape 调用[`Self::reshape`]，并将数据复制到 Tensor 中
+    ///
+    /// `array`不是标准内存布局(如转置或切片后的视图)时按逻辑顺序复制
+    pub fn copy_from_array<T: Element, D: Dimension>(
+        &mut self,
+        array: &ArrayView<T, D>,
+    ) -> Result<()> {
+        let shape = array.shape().iter().map(|&s| s as i32).collect::<Vec<_>>();
+        self.reshape(&shape);
+        match array.as_slice() {
+            Some(data) => self.copy_from(data),
+            None => self.copy_from(&array.iter().copied().collect::<Vec<_>>()),
+        }
+    }
+
+    /// 将 Tensor 中的数据复制到新的`ArrayD`中
+    ///
+    /// 输入类型和[`Self::data_type`]不匹配时获取失败
+    pub fn to_array<T: Element>(&self) -> Result<ArrayD<T>> {
+        let shape = self.shape();
+        let data = self.to_vec()?;
+        let len = data.len();
+        ArrayD::from_shape_vec(dim(&shape), data).map_err(|_| Error::ShapeMismatch { shape, len })
+    }
+
+    /// 获取 Tensor 底层数据的视图，用于读取输出数据
+    ///
+    /// 如果底层数据类型([`crate::common::DataType`])不对应或数据不在 CPU 中则返回`None`
+    pub fn view<T: Element>(&self) -> Option<ArrayViewD<'_, T>> {
+        let (place_type, data) = self.as_slice::<T>()?;
+        if place_type != PlaceType::Cpu {
+            return None;
+        }
+        ArrayViewD::from_shape(dim(&self.shape()), data).ok()
+    }
+}
+
+fn dim(shape: &[i32]) -> IxDyn {
+    IxDyn(&shape.iter().map(|&s| s.max(0) as usize).collect::<Vec<_>>())
+}
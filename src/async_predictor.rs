@@ -0,0 +1,136 @@
+//! 异步预测，需要启用`async`特性
+
+use crate::config::Config;
+use crate::host::NamedTensors;
+use crate::predictor::Predictor;
+use crate::{Error, Result};
+use crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender, TrySendError};
+use std::future::Future;
+use std::thread::JoinHandle;
+use tokio::sync::oneshot;
+
+struct Job {
+    inputs: NamedTensors,
+    reply: oneshot::Sender<Result<NamedTensors>>,
+}
+
+/// 异步预测器
+///
+/// 每个工作线程持有一个克隆的预测器(共享模型权重)，从有界队列中获取任务并执行预测，因此不会阻塞异步运行时。
+/// 队列已满时[`Self::submit`]及[`Self::run`]立即返回[`Error::QueueFull`]。预测器设置了[`crate::ShrinkPolicy::idle`]时，
+/// 各工作线程在空闲超过该时间后分别释放内存。释放时不会等待工作线程，见[`Self::shutdown`]。
+///
+/// ``` no_run
+/// use paddle_inference::config::model::Model;
+/// use paddle_inference::{AsyncPredictor, HostTensor, Predictor};
+///
+/// # async fn f() -> paddle_inference::Result<()> {
+/// let predictor = AsyncPredictor::new(Predictor::builder(Model::path("model", "params")), 2, 16)?;
+/// let x = HostTensor::new(vec![1, 3], vec![0.0f32; 3])?;
+/// let outputs = predictor.run(vec![("x".to_string(), x)]).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AsyncPredictor {
+    sender: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl AsyncPredictor {
+    /// 使用`config`创建预测器，并启动`workers`个工作线程，队列最多容纳`capacity`个等待中的任务
+    ///
+    /// `workers`及`capacity`为`0`时按`1`处理，避免创建无法缓存任务的队列
+    pub fn new(config: Config, workers: usize, capacity: usize) -> Result<Self> {
+        Self::from_predictor(config.build()?, workers, capacity)
+    }
+
+    /// 使用`predictor`及其克隆启动`workers`个工作线程，参数同[`Self::new`]，克隆预测器失败时返回错误
+    pub fn from_predictor(predictor: Predictor, workers: usize, capacity: usize) -> Result<Self> {
+        let (sender, receiver) = bounded(capacity.max(1));
+        let workers = workers.max(1);
+        let mut predictors = Vec::with_capacity(workers);
+        for _ in 1..workers {
+            predictors.push(predictor.try_clone()?);
+        }
+        predictors.push(predictor);
+
+        let workers = predictors
+            .into_iter()
+            .map(|predictor| {
+                let receiver = receiver.clone();
+                std::thread::spawn(move || work(predictor, receiver))
+            })
+            .collect();
+        Ok(Self {
+            sender: Some(sender),
+            workers,
+        })
+    }
+
+    /// 将任务加入队列，返回的`Future`在预测完成后输出所有输出 Tensor
+    ///
+    /// 队列已满时返回[`Error::QueueFull`]
+    pub fn submit(
+        &self,
+        inputs: NamedTensors,
+    ) -> Result<impl Future<Output = Result<NamedTensors>> + Send + 'static> {
+        let (reply, receiver) = oneshot::channel();
+        let sender = self.sender.as_ref().ok_or(Error::WorkerStopped)?;
+        sender
+            .try_send(Job { inputs, reply })
+            .map_err(|e| match e {
+                TrySendError::Full(_) => Error::QueueFull,
+                TrySendError::Disconnected(_) => Error::WorkerStopped,
+            })?;
+        Ok(async move { receiver.await.map_err(|_| Error::WorkerStopped)? })
+    }
+
+    /// 设置输入并执行预测，返回所有输出 Tensor，等同于`self.submit(inputs)?.await`
+    pub async fn run(&self, inputs: NamedTensors) -> Result<NamedTensors> {
+        self.submit(inputs)?.await
+    }
+}
+
+impl AsyncPredictor {
+    /// 停止接收任务，等待队列中的任务完成且所有工作线程退出
+    ///
+    /// 通过`spawn_blocking`等待工作线程，不会阻塞异步运行时
+    pub async fn shutdown(mut self) {
+        self.sender.take();
+        let workers = std::mem::take(&mut self.workers);
+        let _ = tokio::task::spawn_blocking(move || {
+            for worker in workers {
+                let _ = worker.join();
+            }
+        })
+        .await;
+    }
+}
+
+impl Drop for AsyncPredictor {
+    /// 停止接收任务，工作线程完成队列中的任务后自行退出
+    ///
+    /// 不会等待工作线程，因此可以在异步运行时中释放。需要等待时请使用[`Self::shutdown`]
+    fn drop(&mut self) {
+        self.sender.take();
+    }
+}
+
+fn work(mut predictor: Predictor, receiver: Receiver<Job>) {
+    let idle = predictor.shrink_policy().idle;
+    loop {
+        let job = match idle {
+            Some(idle) => receiver.recv_timeout(idle),
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match job {
+            Ok(Job { inputs, reply }) => {
+                let _ = reply.send(predictor.run_host(&inputs));
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                predictor.shrink_if_idle();
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
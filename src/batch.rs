@@ -0,0 +1,449 @@
+//! 动态批处理
+
+use crate::common::DataType;
+use crate::host::{HostData, HostTensor, NamedTensors};
+use crate::predictor::Predictor;
+use crate::tensor::Element;
+use crate::{Error, Result};
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// [`Batcher`]的设置
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchOptions {
+    /// 每次预测最多合并的请求数量，`0`时按`1`处理
+    pub max_batch_size: usize,
+    /// 第一个请求到达后最多等待的时间
+    pub max_wait: Duration,
+    /// 是否将 shape 不同的输入用默认值(`0`)填充到批次中每一维的最大值
+    ///
+    /// 不填充时只有 shape 完全相同的请求才会被合并。填充时输出保持填充后的大小，只有[`Self::crop`]中声明的维度
+    /// 会被裁剪为对应请求的原始大小
+    pub padding: bool,
+    /// 填充时需要裁剪的输出维度及其对应的输入维度
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub crop: Vec<CropDim>,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            max_batch_size: 8,
+            max_wait: Duration::from_millis(5),
+            padding: false,
+            crop: vec![],
+        }
+    }
+}
+
+/// 输出维度与输入维度的对应关系，维度均不包含批次维度
+///
+/// 例如序列标注模型的输入`x`为`[N, L]`、输出`y`为`[N, L, C]`时，`y`的第`0`维跟随`x`的第`0`维，而类别数`C`
+/// 不应被裁剪，即使它恰好等于填充后的长度。输出、输入名称或维度不存在时不裁剪
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CropDim {
+    /// 输出名称
+    pub output: String,
+    /// 输出中需要裁剪的维度
+    pub output_dim: usize,
+    /// 该维度跟随的输入名称
+    pub input: String,
+    /// 输入中对应的维度
+    pub input_dim: usize,
+}
+
+impl CropDim {
+    /// 输出`output`的第`output_dim`维跟随输入`input`的第`input_dim`维
+    pub fn new(output: &str, output_dim: usize, input: &str, input_dim: usize) -> Self {
+        Self {
+            output: output.to_string(),
+            output_dim,
+            input: input.to_string(),
+            input_dim,
+        }
+    }
+}
+
+struct Request {
+    inputs: NamedTensors,
+    arrived: Instant,
+    reply: Sender<Result<NamedTensors>>,
+}
+
+#[derive(Default)]
+struct State {
+    pending: VecDeque<Request>,
+    closed: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    available: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 动态批处理器
+///
+/// 收集多个线程提交的单个样本，在数量达到[`BatchOptions::max_batch_size`]或等待超过
+/// [`BatchOptions::max_wait`]后沿第一维合并为一个批次，执行一次预测，再将每个输出沿第一维拆分后返回给对应的请求。
+///
+/// 提交的样本**不包含**批次维度，例如模型输入为`[N, 3, H, W]`时样本的 shape 为`[3, H, W]`；返回的输出同样不包含批次维度。
+///
+/// ``` no_run
+/// use paddle_inference::config::model::Model;
+/// use paddle_inference::{BatchOptions, Batcher, HostTensor, Predictor};
+///
+/// let predictor = Predictor::builder(Model::path("model", "params")).build()?;
+/// let batcher = Batcher::new(predictor, BatchOptions::default());
+/// let x = HostTensor::new(vec![3], vec![0.0f32; 3])?;
+/// let outputs = batcher.run(vec![("x".to_string(), x)])?;
+/// # Ok::<_, paddle_inference::Error>(())
+/// ```
+pub struct Batcher {
+    shared: Arc<Shared>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Batcher {
+    /// 启动使用`predictor`执行预测的工作线程
+    pub fn new(predictor: Predictor, options: BatchOptions) -> Self {
+        let shared = Arc::new(Shared {
+            state: Default::default(),
+            available: Condvar::new(),
+        });
+        let worker = {
+            let shared = shared.clone();
+            std::thread::spawn(move || work(predictor, options, shared))
+        };
+        Self {
+            shared,
+            worker: Some(worker),
+        }
+    }
+
+    /// 提交单个样本并阻塞直到所在批次预测完成，返回该样本的所有输出
+    ///
+    /// 所在批次预测失败时，批次中的所有请求都返回该错误
+    pub fn run(&self, inputs: NamedTensors) -> Result<NamedTensors> {
+        let (reply, receiver) = channel();
+        {
+            let mut state = self.shared.lock();
+            if state.closed {
+                return Err(Error::WorkerStopped);
+            }
+            state.pending.push_back(Request {
+                inputs,
+                arrived: Instant::now(),
+                reply,
+            });
+        }
+        self.shared.available.notify_all();
+        receiver.recv().map_err(|_| Error::WorkerStopped)?
+    }
+}
+
+impl Drop for Batcher {
+    /// 处理完所有等待中的请求后停止工作线程
+    fn drop(&mut self) {
+        self.shared.lock().closed = true;
+        self.shared.available.notify_all();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn work(mut predictor: Predictor, options: BatchOptions, shared: Arc<Shared>) {
+    let max_batch_size = options.max_batch_size.max(1);
+    loop {
+        let batch = {
+            let mut state = shared.lock();
+            while state.pending.is_empty() && !state.closed {
+                state = shared
+                    .available
+                    .wait(state)
+                    .unwrap_or_else(|e| e.into_inner());
+            }
+            let Some(first) = state.pending.front() else {
+                return;
+            };
+
+            let deadline = first.arrived + options.max_wait;
+            while count_compatible(&state.pending, options.padding) < max_batch_size
+                && !state.closed
+            {
+                let now = Instant::now();
+                if now >= deadline {
+                    break;
+                }
+                state = shared
+                    .available
+                    .wait_timeout(state, deadline - now)
+                    .unwrap_or_else(|e| e.into_inner())
+                    .0;
+            }
+            take_batch(&mut state.pending, max_batch_size, options.padding)
+        };
+
+        let samples = batch.iter().map(|r| &r.inputs[..]).collect::<Vec<_>>();
+        match run_batch(&mut predictor, &samples, &options) {
+            Ok(outputs) => {
+                for (request, outputs) in batch.into_iter().zip(outputs) {
+                    let _ = request.reply.send(Ok(outputs));
+                }
+            }
+            Err(e) => {
+                for request in batch {
+                    let _ = request.reply.send(Err(e.clone()));
+                }
+            }
+        }
+    }
+}
+
+/// 从队列中按顺序取出最多`max`个可以与第一个请求合并的请求，其余请求保留在队列中
+fn take_batch(pending: &mut VecDeque<Request>, max: usize, padding: bool) -> Vec<Request> {
+    let mut batch: Vec<Request> = vec![];
+    let mut rest = VecDeque::new();
+    while let Some(request) = pending.pop_front() {
+        let fits = match batch.first() {
+            None => true,
+            Some(first) => batch.len() < max && compatible(&first.inputs, &request.inputs, padding),
+        };
+        if fits {
+            batch.push(request);
+        } else {
+            rest.push_back(request);
+        }
+    }
+    *pending = rest;
+    batch
+}
+
+/// 队列中可以与第一个请求合并的请求数量(包括第一个请求)
+fn count_compatible(pending: &VecDeque<Request>, padding: bool) -> usize {
+    let Some(first) = pending.front() else {
+        return 0;
+    };
+    pending
+        .iter()
+        .filter(|r| compatible(&first.inputs, &r.inputs, padding))
+        .count()
+}
+
+fn compatible(a: &[(String, HostTensor)], b: &[(String, HostTensor)], padding: bool) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|((na, ta), (nb, tb))| {
+            na == nb
+                && ta.data_type() == tb.data_type()
+                && if padding {
+                    ta.shape().len() == tb.shape().len()
+                } else {
+                    ta.shape() == tb.shape()
+                }
+        })
+}
+
+fn run_batch(
+    predictor: &mut Predictor,
+    samples: &[&[(String, HostTensor)]],
+    options: &BatchOptions,
+) -> Result<Vec<NamedTensors>> {
+    let batch_size = samples.len();
+    let mut inputs = vec![];
+    for (i, (name, _)) in samples[0].iter().enumerate() {
+        let tensors = samples.iter().map(|s| &s[i].1).collect::<Vec<_>>();
+        inputs.push((name.clone(), stack(&tensors, options.padding)?));
+    }
+
+    let outputs = predictor.run_host(&inputs)?;
+    let mut results = vec![vec![]; batch_size];
+    for (name, output) in outputs {
+        let parts = split(output, batch_size)?;
+        for ((result, tensor), sample) in results.iter_mut().zip(parts).zip(samples) {
+            let tensor = if options.padding {
+                let shape = original_shape(&name, tensor.shape(), &options.crop, sample);
+                crop(tensor, &shape)
+            } else {
+                tensor
+            };
+            result.push((name.clone(), tensor));
+        }
+    }
+    Ok(results)
+}
+
+/// 填充后的输出`name`(shape 为`shape`)对应的原始 shape：`dims`中声明的维度使用`sample`中对应输入的大小
+fn original_shape(
+    name: &str,
+    shape: &[usize],
+    dims: &[CropDim],
+    sample: &[(String, HostTensor)],
+) -> Vec<usize> {
+    let mut shape = shape.to_vec();
+    for dim in dims.iter().filter(|d| d.output == name) {
+        let size = sample
+            .iter()
+            .find(|(n, _)| *n == dim.input)
+            .and_then(|(_, t)| t.shape().get(dim.input_dim));
+        if let (Some(s), Some(&size)) = (shape.get_mut(dim.output_dim), size) {
+            *s = (*s).min(size);
+        }
+    }
+    shape
+}
+
+/// 从`tensor`中取出 shape 为`shape`的起始部分，`shape`的每一维都不大于`tensor`
+fn crop(tensor: HostTensor, shape: &[usize]) -> HostTensor {
+    if tensor.shape() == shape {
+        return tensor;
+    }
+
+    macro_rules! crop {
+        ($( $variant: ident ),*) => {
+            match tensor.data() {
+                $(
+                    HostData::$variant(v) => {
+                        let mut data = vec![Default::default(); shape.iter().product()];
+                        copy_cropped(v, tensor.shape(), &mut data, shape);
+                        HostData::$variant(data)
+                    }
+                )*
+            }
+        };
+    }
+
+    let data = crop!(Float32, Int64, Int32, Uint8, Int8);
+    HostTensor::from_data(shape.to_vec(), data).unwrap()
+}
+
+/// 沿新的第一维合并`tensors`，需要填充时按每一维的最大值填充
+fn stack(tensors: &[&HostTensor], padding: bool) -> Result<HostTensor> {
+    let mut shape = tensors[0].shape().to_vec();
+    if padding {
+        for t in tensors {
+            for (s, &d) in shape.iter_mut().zip(t.shape()) {
+                *s = (*s).max(d);
+            }
+        }
+    }
+
+    match tensors[0].data_type() {
+        DataType::Float32 => stack_typed::<f32>(tensors, shape),
+        DataType::Int64 => stack_typed::<i64>(tensors, shape),
+        DataType::Int32 => stack_typed::<i32>(tensors, shape),
+        DataType::Uint8 => stack_typed::<u8>(tensors, shape),
+        DataType::Int8 => stack_typed::<i8>(tensors, shape),
+        DataType::Unknown => unreachable!("HostTensor 的数据类型不会为 Unknown"),
+    }
+}
+
+fn stack_typed<T: Element>(tensors: &[&HostTensor], shape: Vec<usize>) -> Result<HostTensor> {
+    let size = shape.iter().product::<usize>();
+    let mut data = vec![T::default(); size * tensors.len()];
+    for (i, t) in tensors.iter().enumerate() {
+        let src = t.as_slice::<T>().unwrap();
+        copy_padded(src, t.shape(), &mut data[i * size..(i + 1) * size], &shape);
+    }
+    let mut batch_shape = vec![tensors.len()];
+    batch_shape.extend(shape);
+    HostTensor::new(batch_shape, data)
+}
+
+/// 将 shape 为`src_shape`的数据复制到 shape 为`dst_shape`的数据的起始位置，`dst_shape`的每一维都不小于`src_shape`
+fn copy_padded<T: Copy>(src: &[T], src_shape: &[usize], dst: &mut [T], dst_shape: &[usize]) {
+    match src_shape {
+        [] => dst[..1].copy_from_slice(&src[..1]),
+        [n] => dst[..*n].copy_from_slice(&src[..*n]),
+        [n, rest @ ..] => {
+            let s = rest.iter().product::<usize>();
+            let d = dst_shape[1..].iter().product::<usize>();
+            for i in 0..*n {
+                copy_padded(
+                    &src[i * s..(i + 1) * s],
+                    rest,
+                    &mut dst[i * d..(i + 1) * d],
+                    &dst_shape[1..],
+                );
+            }
+        }
+    }
+}
+
+/// 将 shape 为`src_shape`的数据的起始部分复制到 shape 为`dst_shape`的数据中，`dst_shape`的每一维都不大于`src_shape`
+fn copy_cropped<T: Copy>(src: &[T], src_shape: &[usize], dst: &mut [T], dst_shape: &[usize]) {
+    match dst_shape {
+        [] => dst[..1].copy_from_slice(&src[..1]),
+        [n] => dst[..*n].copy_from_slice(&src[..*n]),
+        [n, rest @ ..] => {
+            let s = src_shape[1..].iter().product::<usize>();
+            let d = rest.iter().product::<usize>();
+            for i in 0..*n {
+                copy_cropped(
+                    &src[i * s..(i + 1) * s],
+                    &src_shape[1..],
+                    &mut dst[i * d..(i + 1) * d],
+                    rest,
+                );
+            }
+        }
+    }
+}
+
+/// 沿第一维将`tensor`拆分为`n`个 Tensor，第一维不等于`n`时返回[`Error::ShapeMismatch`]
+fn split(tensor: HostTensor, n: usize) -> Result<Vec<HostTensor>> {
+    let (shape, data) = tensor.into_parts();
+    if shape.first() != Some(&n) {
+        return Err(Error::ShapeMismatch {
+            shape: shape.iter().map(|&s| s as i32).collect(),
+            len: n,
+        });
+    }
+    let sample_shape = shape[1..].to_vec();
+    let size = sample_shape.iter().product::<usize>();
+
+    macro_rules! split {
+        ($( $variant: ident ),*) => {
+            match data {
+                $(
+                    HostData::$variant(v) => (0..n)
+                        .map(|i| HostData::$variant(v[i * size..(i + 1) * size].to_vec()))
+                        .collect::<Vec<_>>(),
+                )*
+            }
+        };
+    }
+
+    split!(Float32, Int64, Int32, Uint8, Int8)
+        .into_iter()
+        .map(|data| HostTensor::from_data(sample_shape.clone(), data))
+        .collect()
+}
+
+#[test]
+fn test_stack_and_split() {
+    let a = HostTensor::new(vec![1, 2], vec![1i32, 2]).unwrap();
+    let b = HostTensor::new(vec![2, 1], vec![3i32, 4]).unwrap();
+    let batch = stack(&[&a, &b], true).unwrap();
+    assert_eq!(batch.shape(), [2, 2, 2]);
+    assert_eq!(batch.as_slice::<i32>().unwrap(), [1, 2, 0, 0, 3, 0, 4, 0]);
+
+    let parts = split(batch, 2).unwrap();
+    assert_eq!(parts[0].shape(), [2, 2]);
+    assert_eq!(parts[1].as_slice::<i32>().unwrap(), [3, 0, 4, 0]);
+    assert!(split(a.clone(), 2).is_err());
+
+    let cropped = crop(parts[1].clone(), b.shape());
+    assert_eq!(cropped, b);
+    assert_eq!(crop(parts[0].clone(), a.shape()), a);
+}
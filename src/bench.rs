@@ -0,0 +1,283 @@
+//! 性能测试
+
+use crate::common::DataType;
+use crate::config::Config;
+use crate::host::{HostTensor, NamedTensors};
+use crate::predictor::Predictor;
+use crate::{Error, Result};
+use std::fmt::{Display, Formatter};
+use std::thread::ScopedJoinHandle;
+use std::time::{Duration, Instant};
+
+/// 性能测试的输入
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchInput {
+    /// 输入名称
+    pub name: String,
+    /// 输入的 shape
+    pub shape: Vec<usize>,
+    /// 数据类型，为`None`时使用模型中声明的数据类型
+    pub data_type: Option<DataType>,
+}
+
+impl BenchInput {
+    pub fn new<S: ToString>(name: S, shape: Vec<usize>) -> Self {
+        Self {
+            name: name.to_string(),
+            shape,
+            data_type: None,
+        }
+    }
+
+    /// 设置数据类型
+    pub fn data_type(mut self, data_type: DataType) -> Self {
+        self.data_type = Some(data_type);
+        self
+    }
+}
+
+/// [`benchmark`]的设置
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchOptions {
+    /// 输入，数据均为`0`
+    pub inputs: Vec<BenchInput>,
+    /// 每个预测器在测量前的预热次数
+    pub warmup: usize,
+    /// 每个预测器测量的次数
+    pub iterations: usize,
+    /// 并行运行的预测器数量，`0`时按`1`处理。第一个预测器之外的预测器通过克隆创建
+    pub threads: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            inputs: vec![],
+            warmup: 10,
+            iterations: 100,
+            threads: 1,
+        }
+    }
+}
+
+/// 延迟统计，单位为毫秒
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Latency {
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl Latency {
+    /// 统计`samples`，百分位数使用最近秩法
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut ms = samples
+            .iter()
+            .map(|d| d.as_secs_f64() * 1000.0)
+            .collect::<Vec<_>>();
+        ms.sort_by(f64::total_cmp);
+        let percentile = |p: f64| {
+            let rank = (p / 100.0 * ms.len() as f64).ceil() as usize;
+            ms[rank.clamp(1, ms.len()) - 1]
+        };
+        Self {
+            min: ms[0],
+            mean: ms.iter().sum::<f64>() / ms.len() as f64,
+            p50: percentile(50.0),
+            p90: percentile(90.0),
+            p99: percentile(99.0),
+            max: ms[ms.len() - 1],
+        }
+    }
+}
+
+/// 性能测试结果
+///
+/// 实现了[`Display`]用于输出文本，启用`serde`特性时可以序列化为 JSON 等格式
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchReport {
+    /// 并行运行的预测器数量
+    pub threads: usize,
+    /// 所有预测器测量的总次数
+    pub iterations: usize,
+    /// 测量阶段的总耗时，单位为秒
+    pub elapsed: f64,
+    /// 每秒完成的预测次数
+    pub throughput: f64,
+    /// 单次预测的延迟
+    pub latency: Latency,
+    /// 进程的内存占用峰值，单位为字节。仅支持 Linux，其余平台为`None`
+    ///
+    /// 该值为进程启动以来的峰值，包括测试前分配的内存
+    pub peak_rss: Option<u64>,
+}
+
+impl Display for BenchReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "预测器数量: {}", self.threads)?;
+        writeln!(f, "测量次数: {}", self.iterations)?;
+        writeln!(f, "总耗时: {:.3} s", self.elapsed)?;
+        writeln!(f, "吞吐量: {:.2} 次/s", self.throughput)?;
+        let l = &self.latency;
+        writeln!(
+            f,
+            "延迟(ms): min {:.3}, mean {:.3}, p50 {:.3}, p90 {:.3}, p99 {:.3}, max {:.3}",
+            l.min, l.mean, l.p50, l.p90, l.p99, l.max
+        )?;
+        match self.peak_rss {
+            Some(rss) => write!(f, "内存峰值: {:.1} MiB", rss as f64 / 1024.0 / 1024.0),
+            None => write!(f, "内存峰值: 未知"),
+        }
+    }
+}
+
+/// 使用`config`创建预测器并测试性能
+///
+/// 每个预测器先预热[`BenchOptions::warmup`]次，所有预测器预热完成后再同时测量[`BenchOptions::iterations`]次。
+/// 每次预测的延迟包括复制输入、执行预测及复制输出
+///
+/// ``` no_run
+/// use paddle_inference::config::model::Model;
+/// use paddle_inference::{benchmark, BenchInput, BenchOptions, Predictor};
+///
+/// let config = Predictor::builder(Model::path("model", "params"));
+/// let options = BenchOptions {
+///     inputs: vec![BenchInput::new("x", vec![1, 3, 224, 224])],
+///     threads: 4,
+///     ..Default::default()
+/// };
+/// let report = benchmark(config, &options)?;
+/// println!("{}", report);
+/// # Ok::<_, paddle_inference::Error>(())
+/// ```
+pub fn benchmark(config: Config, options: &BenchOptions) -> Result<BenchReport> {
+    let threads = options.threads.max(1);
+    let mut predictor = config.build()?;
+    let inputs = inputs(&mut predictor, &options.inputs)?;
+    let mut predictors = (1..threads)
+        .map(|_| predictor.try_clone())
+        .collect::<Result<Vec<_>>>()?;
+    predictors.push(predictor);
+
+    // 所有预测器预热完成后再计时，某个线程失败或 panic 时不会阻塞其他线程
+    std::thread::scope(|s| {
+        let handles = predictors
+            .iter_mut()
+            .map(|predictor| {
+                let inputs = &inputs;
+                s.spawn(move || {
+                    (0..options.warmup).try_for_each(|_| predictor.run_host(inputs).map(drop))
+                })
+            })
+            .collect::<Vec<_>>();
+        join_all(handles)
+    })?;
+
+    let start = Instant::now();
+    let samples = std::thread::scope(|s| {
+        let handles = predictors
+            .iter_mut()
+            .map(|predictor| {
+                let inputs = &inputs;
+                s.spawn(move || {
+                    let mut samples = Vec::with_capacity(options.iterations);
+                    for _ in 0..options.iterations {
+                        let start = Instant::now();
+                        predictor.run_host(inputs)?;
+                        samples.push(start.elapsed());
+                    }
+                    Ok(samples)
+                })
+            })
+            .collect::<Vec<_>>();
+        join_all(handles)
+    });
+    let elapsed = start.elapsed();
+
+    let samples = samples?.concat();
+    let elapsed = elapsed.as_secs_f64();
+    Ok(BenchReport {
+        threads,
+        iterations: samples.len(),
+        elapsed,
+        throughput: if elapsed > 0.0 {
+            samples.len() as f64 / elapsed
+        } else {
+            0.0
+        },
+        latency: Latency::from_samples(&samples),
+        peak_rss: peak_rss(),
+    })
+}
+
+/// 等待所有工作线程，线程 panic 时返回[`Error::WorkerStopped`]
+fn join_all<T>(handles: Vec<ScopedJoinHandle<'_, Result<T>>>) -> Result<Vec<T>> {
+    handles
+        .into_iter()
+        .map(|h| h.join().unwrap_or(Err(Error::WorkerStopped)))
+        .collect()
+}
+
+/// 创建数据均为`0`的输入
+fn inputs(predictor: &mut Predictor, inputs: &[BenchInput]) -> Result<NamedTensors> {
+    let names: Vec<String> = predictor.input_names().into();
+    inputs
+        .iter()
+        .map(|input| {
+            if !names.contains(&input.name) {
+                return Err(Error::TensorNotFound(input.name.clone()));
+            }
+            let data_type = match input.data_type {
+                Some(data_type) => data_type,
+                None => predictor.input(&input.name)?.data_type(),
+            };
+            let shape = input.shape.clone();
+            let tensor = match data_type {
+                DataType::Float32 => HostTensor::zeros::<f32>(shape)?,
+                DataType::Int64 => HostTensor::zeros::<i64>(shape)?,
+                DataType::Int32 => HostTensor::zeros::<i32>(shape)?,
+                DataType::Uint8 => HostTensor::zeros::<u8>(shape)?,
+                DataType::Int8 => HostTensor::zeros::<i8>(shape)?,
+                DataType::Unknown => return Err(Error::UnknownDataType(input.name.clone())),
+            };
+            Ok((input.name.clone(), tensor))
+        })
+        .collect()
+}
+
+/// 读取`/proc/self/status`中的`VmHWM`
+#[cfg(target_os = "linux")]
+fn peak_rss() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+    let kb = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn peak_rss() -> Option<u64> {
+    None
+}
+
+#[test]
+fn test_latency() {
+    let samples = (1..=100).map(Duration::from_millis).collect::<Vec<_>>();
+    let latency = Latency::from_samples(&samples);
+    assert_eq!(latency.min, 1.0);
+    assert_eq!(latency.p50, 50.0);
+    assert_eq!(latency.p90, 90.0);
+    assert_eq!(latency.p99, 99.0);
+    assert_eq!(latency.max, 100.0);
+    assert!((latency.mean - 50.5).abs() < 1e-9);
+    assert_eq!(Latency::from_samples(&[]), Latency::default());
+}
@@ -1,5 +1,6 @@
 use crate::call;
 use crate::ctypes::{PD_OneDimArrayCstr, PD_OneDimArrayCstrDestroy};
+use crate::runtime::Runtime;
 use std::borrow::Cow;
 use std::ffi::{CStr, CString, NulError};
 use std::os::raw::c_char;
@@ -7,11 +8,17 @@ use std::os::raw::c_char;
 pub struct OneDimArrayCstr {
     ptr: *mut PD_OneDimArrayCstr,
     data: Option<Vec<*const c_char>>,
+    runtime: Option<Runtime>,
 }
 
 impl OneDimArrayCstr {
-    pub fn from_ptr(ptr: *mut PD_OneDimArrayCstr) -> Self {
-        Self { ptr, data: None }
+    /// 封装由`runtime`创建的数组，释放时调用`runtime`中对应的销毁函数
+    pub fn from_ptr(runtime: &Runtime, ptr: *mut PD_OneDimArrayCstr) -> Self {
+        Self {
+            ptr,
+            data: None,
+            runtime: Some(runtime.clone()),
+        }
     }
 
     pub fn new<S: AsRef<str>, I: Iterator<Item = S>>(iter: I) -> Result<Self, NulError> {
@@ -31,6 +38,7 @@ impl OneDimArrayCstr {
         Ok(Self {
             ptr,
             data: Some(data),
+            runtime: None,
         })
     }
 
@@ -42,7 +50,7 @@ impl OneDimArrayCstr {
         self.len() == 0
     }
 
-    pub fn get(&self, idx: usize) -> Option<Cow<str>> {
+    pub fn get(&self, idx: usize) -> Option<Cow<'_, str>> {
         if idx >= self.len() {
             return None;
         }
@@ -62,13 +70,13 @@ impl OneDimArrayCstr {
 impl Drop for OneDimArrayCstr {
     fn drop(&mut self) {
         if let Some(data) = &mut self.data {
-            unsafe { Box::from_raw(self.ptr) };
+            drop(unsafe { Box::from_raw(self.ptr) });
             for ptr in data {
                 let ptr = *ptr;
-                unsafe { CString::from_raw((*ptr) as *mut _) };
+                drop(unsafe { CString::from_raw((*ptr) as *mut _) });
             }
-        } else {
-            call! { PD_OneDimArrayCstrDestroy(self.ptr) }
+        } else if let Some(runtime) = &self.runtime {
+            call! { runtime => PD_OneDimArrayCstrDestroy(self.ptr) }
         }
     }
 }
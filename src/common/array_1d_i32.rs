@@ -1,15 +1,22 @@
 use crate::call;
 use crate::ctypes::{PD_OneDimArrayInt32, PD_OneDimArrayInt32Destroy};
+use crate::runtime::Runtime;
 use std::ops::{Deref, DerefMut};
 
 pub struct OneDimArrayInt32 {
     ptr: *mut PD_OneDimArrayInt32,
     data: Option<Vec<i32>>,
+    runtime: Option<Runtime>,
 }
 
 impl OneDimArrayInt32 {
-    pub fn from_ptr(ptr: *mut PD_OneDimArrayInt32) -> Self {
-        Self { ptr, data: None }
+    /// 封装由`runtime`创建的数组，释放时调用`runtime`中对应的销毁函数
+    pub fn from_ptr(runtime: &Runtime, ptr: *mut PD_OneDimArrayInt32) -> Self {
+        Self {
+            ptr,
+            data: None,
+            runtime: Some(runtime.clone()),
+        }
     }
 
     pub fn with_size(size: usize) -> Self {
@@ -27,6 +34,7 @@ impl OneDimArrayInt32 {
         Self {
             ptr,
             data: Some(data),
+            runtime: None,
         }
     }
 }
@@ -34,9 +42,9 @@ impl OneDimArrayInt32 {
 impl Drop for OneDimArrayInt32 {
     fn drop(&mut self) {
         if self.data.is_some() {
-            unsafe { Box::from_raw(self.ptr) };
-        } else {
-            call! { PD_OneDimArrayInt32Destroy(self.ptr) }
+            drop(unsafe { Box::from_raw(self.ptr) });
+        } else if let Some(runtime) = &self.runtime {
+            call! { runtime => PD_OneDimArrayInt32Destroy(self.ptr) }
         }
     }
 }
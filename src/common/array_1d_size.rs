@@ -1,15 +1,22 @@
 use crate::call;
 use crate::ctypes::{PD_OneDimArraySize, PD_OneDimArraySizeDestroy};
+use crate::runtime::Runtime;
 use std::ops::{Deref, DerefMut};
 
 pub struct OneDimArraySize {
     pub(crate) ptr: *mut PD_OneDimArraySize,
     data: Option<Vec<usize>>,
+    runtime: Option<Runtime>,
 }
 
 impl OneDimArraySize {
-    pub fn from_ptr(ptr: *mut PD_OneDimArraySize) -> Self {
-        Self { ptr, data: None }
+    /// 封装由`runtime`创建的数组，释放时调用`runtime`中对应的销毁函数
+    pub fn from_ptr(runtime: &Runtime, ptr: *mut PD_OneDimArraySize) -> Self {
+        Self {
+            ptr,
+            data: None,
+            runtime: Some(runtime.clone()),
+        }
     }
 
     pub fn with_size(size: usize) -> Self {
@@ -27,6 +34,7 @@ impl OneDimArraySize {
         Self {
             ptr,
             data: Some(data),
+            runtime: None,
         }
     }
 }
@@ -34,9 +42,9 @@ impl OneDimArraySize {
 impl Drop for OneDimArraySize {
     fn drop(&mut self) {
         if self.data.is_some() {
-            unsafe { Box::from_raw(self.ptr) };
-        } else {
-            call! { PD_OneDimArraySizeDestroy(self.ptr) }
+            drop(unsafe { Box::from_raw(self.ptr) });
+        } else if let Some(runtime) = &self.runtime {
+            call! { runtime => PD_OneDimArraySizeDestroy(self.ptr) }
         }
     }
 }
@@ -1,20 +1,49 @@
 use crate::call;
 use crate::common::OneDimArraySize;
 use crate::ctypes::{PD_OneDimArraySize, PD_TwoDimArraySize, PD_TwoDimArraySizeDestroy};
+use crate::runtime::Runtime;
 use std::ops::Deref;
 
+/// 二维`usize`数组，数据始终由 Rust 持有
 pub struct TwoDimArraySize {
     pub(crate) ptr: *mut PD_TwoDimArraySize,
-    data: Option<(Vec<OneDimArraySize>, Vec<*mut PD_OneDimArraySize>)>,
+    data: Vec<OneDimArraySize>,
+    _data_ptr: Vec<*mut PD_OneDimArraySize>,
 }
 
 impl TwoDimArraySize {
-    pub fn from_ptr(ptr: *mut PD_TwoDimArraySize) -> Self {
-        Self { ptr, data: None }
+    /// 复制由`runtime`创建的数组，复制后立即调用`runtime`中对应的销毁函数释放`ptr`
+    ///
+    /// **注意：** 与其它数组的`from_ptr`相同，调用者需要保证`ptr`为空或指向有效的数组
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn from_ptr(runtime: &Runtime, ptr: *mut PD_TwoDimArraySize) -> Self {
+        if ptr.is_null() {
+            return Self::new(vec![]);
+        }
+        let rows = unsafe {
+            let array = &*ptr;
+            (0..array.size)
+                .map(|i| {
+                    let row = &**array.data.add(i);
+                    if row.data.is_null() {
+                        vec![]
+                    } else {
+                        std::slice::from_raw_parts(row.data as *const usize, row.size).to_vec()
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        call! { runtime => PD_TwoDimArraySizeDestroy(ptr) };
+        Self::new(
+            rows.into_iter()
+                .map(OneDimArraySize::new)
+                .collect::<Vec<_>>(),
+        )
     }
 
     pub fn new<V: Into<Vec<OneDimArraySize>>>(v: V) -> Self {
         let data = v.into();
+        // 每行的`ptr`及`data_ptr`的缓冲区均位于堆上，移动`Vec`不会使指针失效
         let mut data_ptr = data.iter().map(|d| d.ptr).collect::<Vec<_>>();
 
         let size = data.len();
@@ -25,18 +54,15 @@ impl TwoDimArraySize {
         let ptr = Box::into_raw(Box::new(array));
         Self {
             ptr,
-            data: Some((data, data_ptr)),
+            data,
+            _data_ptr: data_ptr,
         }
     }
 }
 
 impl Drop for TwoDimArraySize {
     fn drop(&mut self) {
-        if self.data.is_some() {
-            unsafe { Box::from_raw(self.ptr) };
-        } else {
-            call! { PD_TwoDimArraySizeDestroy(self.ptr) }
-        }
+        drop(unsafe { Box::from_raw(self.ptr) });
     }
 }
 
@@ -44,11 +70,29 @@ impl Deref for TwoDimArraySize {
     type Target = [OneDimArraySize];
 
     fn deref(&self) -> &Self::Target {
-        if let Some((data, _)) = &self.data {
-            data.as_slice()
-        } else {
-            let ptr = unsafe { &*self.ptr };
-            unsafe { std::slice::from_raw_parts(ptr.data as *const _, ptr.size) }
-        }
+        self.data.as_slice()
+    }
+}
+
+impl From<Vec<Vec<usize>>> for TwoDimArraySize {
+    fn from(v: Vec<Vec<usize>>) -> Self {
+        Self::new(v.into_iter().map(OneDimArraySize::new).collect::<Vec<_>>())
+    }
+}
+
+impl From<TwoDimArraySize> for Vec<Vec<usize>> {
+    fn from(array: TwoDimArraySize) -> Self {
+        array.iter().map(|row| row.to_vec()).collect()
     }
 }
+
+#[test]
+fn test() {
+    let array = TwoDimArraySize::from(vec![vec![0, 2, 5], vec![]]);
+    let ptr = unsafe { &*array.ptr };
+    assert_eq!(ptr.size, 2);
+    let row = unsafe { &**ptr.data };
+    assert_eq!(row.size, 3);
+    assert_eq!(unsafe { row.data.add(2).read() }, 5);
+    assert_eq!(Vec::<Vec<usize>>::from(array), [vec![0, 2, 5], vec![]]);
+}
@@ -1,8 +1,10 @@
-use crate::call;
 use crate::common::PrecisionType;
 use crate::config::SetConfig;
-use crate::ctypes::{PD_Config, PD_ConfigEnableLiteEngine};
+use crate::ctypes::{Function, PD_Config, PD_ConfigEnableLiteEngine};
+use crate::runtime::Runtime;
+use crate::try_call;
 use crate::utils::to_c_str;
+use crate::Result;
 
 /// Lite 子图
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -19,7 +21,13 @@ pub struct LiteEngine {
 }
 
 impl SetConfig for LiteEngine {
-    fn set_to(self, config: *mut PD_Config) {
+    const NAME: &'static str = "lite";
+
+    fn functions(&self) -> Vec<&'static str> {
+        vec![PD_ConfigEnableLiteEngine::NAME]
+    }
+
+    fn set_to(self, runtime: &Runtime, config: *mut PD_Config) -> Result<()> {
         let LiteEngine {
             precision,
             zero_copy,
@@ -38,8 +46,8 @@ impl SetConfig for LiteEngine {
             .unzip::<_, _, Vec<_>, Vec<_>>();
         let ops_filter_num = ops_filter_ptr.len();
 
-        call! {
-            PD_ConfigEnableLiteEngine(
+        try_call! {
+            runtime => PD_ConfigEnableLiteEngine(
                 config,
                 precision,
                 zero_copy,
@@ -49,5 +57,7 @@ impl SetConfig for LiteEngine {
                 ops_filter_ptr.as_mut_ptr()
             )
         };
+
+        Ok(())
     }
 }
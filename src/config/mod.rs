@@ -2,19 +2,27 @@
 
 pub mod lite_engine;
 pub mod model;
+pub mod passes;
+pub mod report;
 pub mod setting;
 
-use crate::call;
+use crate::common::OneDimArrayCstr;
 use crate::config::lite_engine::LiteEngine;
 use crate::config::setting::{Cpu, Gpu, ONNXRuntime, Xpu};
 use crate::ctypes::{
-    PD_Config, PD_ConfigCreate, PD_ConfigDisableFCPadding, PD_ConfigDisableGlogInfo,
-    PD_ConfigEnableMemoryOptim, PD_ConfigProfileEnabled, PD_ConfigSetOptimCacheDir,
-    PD_ConfigSwitchIrDebug, PD_PredictorCreate,
+    Function, PD_Config, PD_ConfigAllPasses, PD_ConfigCollectShapeRangeInfo, PD_ConfigCreate,
+    PD_ConfigDestroy, PD_ConfigDisableFCPadding, PD_ConfigDisableGlogInfo,
+    PD_ConfigEnableMemoryOptim, PD_ConfigEnableProfile, PD_ConfigSetOptimCacheDir,
+    PD_ConfigSwitchIrDebug, PD_ConfigSwitchIrOptim, PD_PredictorCreate,
 };
 use crate::predictor::Predictor;
+use crate::runtime::{Capabilities, Runtime};
+use crate::try_call;
 use crate::utils::to_c_str;
+use crate::{Error, Result};
 use model::Model;
+use passes::Passes;
+use report::ConfigReport;
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone)]
@@ -33,6 +41,8 @@ pub struct Config {
     pub ir_optimization: bool,
     /// 是否在图分析阶段打印 IR，启用后会在每一个 PASS 后生成 dot 文件, 默认关闭
     pub ir_debug: bool,
+    /// IR 优化的 pass 设置
+    pub passes: Passes,
     /// 启用 Lite 子图
     pub lite: Option<LiteEngine>,
     /// 开启内存/显存复用，具体降低内存效果取决于模型结构
@@ -41,12 +51,19 @@ pub struct Config {
     ///
     /// **注意：** 如果当前使用的为 TensorRT INT8 且设置从内存中加载模型，则必须通过该方法来设置缓存路径。
     pub optimization_cache_dir: Option<String>,
+    /// 收集运行时输入的 shape 范围并保存到该文件，用于[`setting::TunedDynamicShape`]
+    pub collect_shape_range_info: Option<String>,
     /// 禁用 FC Padding
     pub disable_fc_padding: bool,
-    /// 打开 Profile，运行结束后会打印所有 OP 的耗时占比。
+    /// 打开 Profile，释放预测器时会打印所有 OP 的耗时统计，可以通过[`crate::ProfileReport`]解析
     pub profile: bool,
     /// 去除 Paddle Inference 运行中的 LOG
     pub disable_log: bool,
+    /// 模型名称，启用`metrics`特性时作为指标的`model`标签，为`None`时使用`default`
+    pub model_name: Option<String>,
+    /// 创建预测器时使用的动态库，为`None`时使用[`Runtime::default_runtime`]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub runtime: Option<Runtime>,
 }
 
 impl Config {
@@ -59,22 +76,38 @@ impl Config {
             onnx_runtime: None,
             ir_optimization: true,
             ir_debug: false,
+            passes: Default::default(),
             lite: None,
             memory_optimization: false,
             optimization_cache_dir: None,
+            collect_shape_range_info: None,
             disable_fc_padding: false,
             profile: false,
             disable_log: false,
+            model_name: None,
+            runtime: None,
         }
     }
 
+    /// 设置创建预测器时使用的动态库
+    pub fn runtime(mut self, runtime: Runtime) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+
+    /// 设置模型名称，启用`metrics`特性时作为指标的`model`标签
+    pub fn model_name<S: ToString>(mut self, name: S) -> Self {
+        self.model_name = Some(name.to_string());
+        self
+    }
+
     /// 去除 Paddle Inference 运行中的 LOG
     pub fn disable_log_info(mut self) -> Self {
         self.disable_log = true;
         self
     }
 
-    /// 打开 Profile，运行结束后会打印所有 OP 的耗时占比。
+    /// 打开 Profile，释放预测器时会打印所有 OP 的耗时统计，可以通过[`crate::ProfileReport`]解析
     pub fn enable_profile(mut self) -> Self {
         self.profile = true;
         self
@@ -94,6 +127,14 @@ impl Config {
         self
     }
 
+    /// 收集运行时输入的 shape 范围，并在预测器销毁时保存到`path`
+    ///
+    /// 使用有代表性的输入运行预测后，可以通过[`setting::TunedDynamicShape`]将该文件作为 TensorRT 的动态 Shape
+    pub fn collect_shape_range_info<S: ToString>(mut self, path: S) -> Self {
+        self.collect_shape_range_info = Some(path.to_string());
+        self
+    }
+
     /// 开启内存/显存复用，具体降低内存效果取决于模型结构
     pub fn enable_memory_optimization(mut self) -> Self {
         self.memory_optimization = true;
@@ -118,6 +159,12 @@ impl Config {
         self
     }
 
+    /// 设置 IR 优化的 pass
+    pub fn passes(mut self, passes: Passes) -> Self {
+        self.passes = passes;
+        self
+    }
+
     pub fn cpu(mut self, cpu: Cpu) -> Self {
         self.cpu = cpu;
         self
@@ -138,7 +185,149 @@ impl Config {
         self
     }
 
-    pub fn build(self) -> Predictor {
+    /// 检查动态库是否支持当前配置，不支持时返回[`Error::Unsupported`]
+    pub fn check(&self, capabilities: &Capabilities) -> Result<()> {
+        self.check_capabilities(capabilities)
+    }
+
+    /// 根据配置创建[`Predictor`]
+    ///
+    /// 使用[`Self::runtime`]设置的动态库创建，未设置时使用[`Runtime::default_runtime`]
+    ///
+    /// 如果出现以下情况则创建失败
+    /// - 动态库加载失败
+    /// - 动态库不支持当前配置，见[`Self::check`]
+    /// - `PD_ConfigCreate`或`PD_PredictorCreate`返回空指针
+    pub fn build(mut self) -> Result<Predictor> {
+        trace_span!(
+            "Config::build",
+            model_name = self.model_name.as_deref(),
+            device = self.device(),
+            tensor_rt = self.gpu.as_ref().is_some_and(|g| g.enable_tensor_rt.is_some()),
+            mkldnn = self.cpu.mkldnn.is_some(),
+            lite = self.lite.is_some(),
+            ir_optimization = self.ir_optimization,
+            passes = ?self.passes
+        );
+        let runtime = self.take_runtime()?;
+        self.check(runtime.capabilities())?;
+
+        let model_name = self.model_name.clone();
+        let config = self.create(&runtime)?;
+        let report = ConfigReport::read(&runtime, config);
+
+        let ptr = try_call! { &runtime => PD_PredictorCreate(config) };
+        if ptr.is_null() {
+            return Err(Error::NullHandle("PD_PredictorCreate"));
+        }
+        Ok(Predictor::from_ptr(runtime, ptr, report, model_name))
+    }
+
+    /// 获取应用当前配置后的 IR 优化 pass 列表
+    ///
+    /// 该方法不会加载模型，使用的动态库与[`Self::build`]相同
+    pub fn all_passes(&self) -> Result<Vec<String>> {
+        let mut config = self.clone();
+        let runtime = config.take_runtime()?;
+        config.check(runtime.capabilities())?;
+        let passes_fn = PD_ConfigAllPasses::load(&runtime)?;
+        let destroy = PD_ConfigDestroy::load(&runtime)?;
+
+        let config = config.create(&runtime)?;
+        let passes = OneDimArrayCstr::from_ptr(&runtime, unsafe { passes_fn(config) });
+        unsafe { destroy(config) };
+        Ok(passes.into())
+    }
+
+    /// 执行预测的设备，用于`tracing`的字段
+    #[cfg(feature = "tracing")]
+    fn device(&self) -> &'static str {
+        if self.gpu.is_some() {
+            "gpu"
+        } else if self.xpu.is_some() {
+            "xpu"
+        } else if self.onnx_runtime.is_some() {
+            "onnxruntime"
+        } else {
+            "cpu"
+        }
+    }
+
+    fn take_runtime(&mut self) -> Result<Runtime> {
+        match self.runtime.take() {
+            Some(runtime) => Ok(runtime),
+            None => Ok(Runtime::default_runtime()?.clone()),
+        }
+    }
+
+    /// 创建`PD_Config`并应用当前配置
+    fn create(self, runtime: &Runtime) -> Result<*mut PD_Config> {
+        let config = try_call! { runtime => PD_ConfigCreate() };
+        if config.is_null() {
+            return Err(Error::NullHandle("PD_ConfigCreate"));
+        }
+
+        if let Err(e) = self.set_to(runtime, config) {
+            if let Ok(destroy) = PD_ConfigDestroy::load(runtime) {
+                unsafe { destroy(config) };
+            }
+            return Err(e);
+        }
+        Ok(config)
+    }
+}
+
+impl SetConfig for Config {
+    const NAME: &'static str = "config";
+
+    fn functions(&self) -> Vec<&'static str> {
+        let mut functions = vec![
+            PD_ConfigCreate::NAME,
+            PD_ConfigDestroy::NAME,
+            PD_ConfigSwitchIrOptim::NAME,
+            PD_ConfigSwitchIrDebug::NAME,
+            PD_ConfigEnableMemoryOptim::NAME,
+            PD_PredictorCreate::NAME,
+        ];
+        if self.optimization_cache_dir.is_some() {
+            functions.push(PD_ConfigSetOptimCacheDir::NAME);
+        }
+        if self.collect_shape_range_info.is_some() {
+            functions.push(PD_ConfigCollectShapeRangeInfo::NAME);
+        }
+        if self.disable_fc_padding {
+            functions.push(PD_ConfigDisableFCPadding::NAME);
+        }
+        if self.profile {
+            functions.push(PD_ConfigEnableProfile::NAME);
+        }
+        if self.disable_log {
+            functions.push(PD_ConfigDisableGlogInfo::NAME);
+        }
+        functions
+    }
+
+    fn check_capabilities(&self, capabilities: &Capabilities) -> Result<()> {
+        check_functions(Self::NAME, self.functions(), capabilities)?;
+        self.model.check_capabilities(capabilities)?;
+        self.passes.check_capabilities(capabilities)?;
+        self.cpu.check_capabilities(capabilities)?;
+        if let Some(g) = &self.gpu {
+            g.check_capabilities(capabilities)?;
+        }
+        if let Some(x) = &self.xpu {
+            x.check_capabilities(capabilities)?;
+        }
+        if let Some(o) = &self.onnx_runtime {
+            o.check_capabilities(capabilities)?;
+        }
+        if let Some(l) = &self.lite {
+            l.check_capabilities(capabilities)?;
+        }
+        Ok(())
+    }
+
+    fn set_to(self, runtime: &Runtime, config: *mut PD_Config) -> Result<()> {
         let Self {
             model,
             cpu,
@@ -147,60 +336,89 @@ impl Config {
             onnx_runtime,
             ir_optimization,
             ir_debug,
+            passes,
             lite,
             memory_optimization,
             optimization_cache_dir,
+            collect_shape_range_info,
             disable_fc_padding,
             profile,
             disable_log,
+            model_name: _,
+            runtime: _,
         } = self;
 
-        let config = call! { PD_ConfigCreate() };
+        model.set_to(runtime, config)?;
 
-        model.set_to(config);
-
-        cpu.set_to(config);
+        cpu.set_to(runtime, config)?;
         if let Some(g) = gpu {
-            g.set_to(config)
+            g.set_to(runtime, config)?;
         }
         if let Some(x) = xpu {
-            x.set_to(config)
+            x.set_to(runtime, config)?;
         }
         if let Some(o) = onnx_runtime {
-            o.set_to(config)
+            o.set_to(runtime, config)?;
         }
 
-        call! { PD_ConfigSwitchIrDebug(config, ir_optimization) };
-        call! { PD_ConfigSwitchIrDebug(config, ir_debug) };
+        try_call! { runtime => PD_ConfigSwitchIrOptim(config, ir_optimization) };
+        try_call! { runtime => PD_ConfigSwitchIrDebug(config, ir_debug) };
+        passes.set_to(runtime, config)?;
 
         if let Some(l) = lite {
-            l.set_to(config)
+            l.set_to(runtime, config)?;
         }
 
-        call! { PD_ConfigEnableMemoryOptim(config, memory_optimization) };
+        try_call! { runtime => PD_ConfigEnableMemoryOptim(config, memory_optimization) };
 
         if let Some(s) = optimization_cache_dir {
             let (_s, cs) = to_c_str(&s);
-            call! { PD_ConfigSetOptimCacheDir(config, cs) };
+            try_call! { runtime => PD_ConfigSetOptimCacheDir(config, cs) };
+        }
+
+        if let Some(s) = collect_shape_range_info {
+            let (_s, cs) = to_c_str(&s);
+            try_call! { runtime => PD_ConfigCollectShapeRangeInfo(config, cs) };
         }
 
         if disable_fc_padding {
-            call! { PD_ConfigDisableFCPadding(config) };
+            try_call! { runtime => PD_ConfigDisableFCPadding(config) };
         }
 
         if profile {
-            call! { PD_ConfigProfileEnabled(config) };
+            try_call! { runtime => PD_ConfigEnableProfile(config) };
         }
 
         if disable_log {
-            call! { PD_ConfigDisableGlogInfo(config) };
+            try_call! { runtime => PD_ConfigDisableGlogInfo(config) };
         }
 
-        let ptr = call! { PD_PredictorCreate(config) };
-        Predictor::from_ptr(ptr)
+        Ok(())
     }
 }
 
 trait SetConfig {
-    fn set_to(self, config: *mut PD_Config);
+    /// 配置名称，用于[`Error::Unsupported`]
+    const NAME: &'static str;
+
+    /// 设置该配置时会调用的函数
+    fn functions(&self) -> Vec<&'static str>;
+
+    /// 检查动态库中是否存在[`Self::functions`]中的所有函数
+    fn check_capabilities(&self, capabilities: &Capabilities) -> Result<()> {
+        check_functions(Self::NAME, self.functions(), capabilities)
+    }
+
+    fn set_to(self, runtime: &Runtime, config: *mut PD_Config) -> Result<()>;
+}
+
+fn check_functions(
+    option: &'static str,
+    functions: Vec<&'static str>,
+    capabilities: &Capabilities,
+) -> Result<()> {
+    match functions.into_iter().find(|f| !capabilities.has(f)) {
+        Some(function) => Err(Error::Unsupported { option, function }),
+        None => Ok(()),
+    }
 }
@@ -1,7 +1,11 @@
-use crate::call;
 use crate::config::SetConfig;
-use crate::ctypes::{PD_Config, PD_ConfigSetModel, PD_ConfigSetModelBuffer, PD_ConfigSetModelDir};
+use crate::ctypes::{
+    Function, PD_Config, PD_ConfigSetModel, PD_ConfigSetModelBuffer, PD_ConfigSetModelDir,
+};
+use crate::runtime::Runtime;
+use crate::try_call;
 use crate::utils::to_c_str;
+use crate::Result;
 
 /// 预测模型
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(untagged))]
@@ -41,11 +45,22 @@ impl Model {
 }
 
 impl SetConfig for Model {
-    fn set_to(self, config: *mut PD_Config) {
+    const NAME: &'static str = "model";
+
+    fn functions(&self) -> Vec<&'static str> {
+        let function = match self {
+            Model::Dir(_) => PD_ConfigSetModelDir::NAME,
+            Model::Path { .. } => PD_ConfigSetModel::NAME,
+            Model::Memory { .. } => PD_ConfigSetModelBuffer::NAME,
+        };
+        vec![function]
+    }
+
+    fn set_to(self, runtime: &Runtime, config: *mut PD_Config) -> Result<()> {
         match self {
             Model::Dir(dir) => {
                 let (_p, ptr) = to_c_str(&dir);
-                call! { PD_ConfigSetModelDir(config, ptr) };
+                try_call! { runtime => PD_ConfigSetModelDir(config, ptr) };
             }
             Model::Path {
                 model_file_path,
@@ -53,11 +68,11 @@ impl SetConfig for Model {
             } => {
                 let (_m, model_path) = to_c_str(&model_file_path);
                 let (_p, params_path) = to_c_str(&params_file_path);
-                call! { PD_ConfigSetModel(config, model_path, params_path) };
+                try_call! { runtime => PD_ConfigSetModel(config, model_path, params_path) };
             }
             Model::Memory { model, params } => {
-                call! {
-                    PD_ConfigSetModelBuffer(
+                try_call! {
+                    runtime => PD_ConfigSetModelBuffer(
                         config,
                         model.as_ptr() as *const _,
                         model.len(),
@@ -67,5 +82,7 @@ impl SetConfig for Model {
                 };
             }
         }
+
+        Ok(())
     }
 }
@@ -0,0 +1,91 @@
+use crate::config::SetConfig;
+use crate::ctypes::{
+    Function, PD_Config, PD_ConfigAppendPass, PD_ConfigDeletePass, PD_ConfigInsertPass,
+};
+use crate::runtime::Runtime;
+use crate::try_call;
+use crate::utils::to_c_str;
+use crate::Result;
+
+/// IR 优化的 pass 设置
+///
+/// 按照[`Self::delete`]、[`Self::insert`]、[`Self::append`]的顺序应用，最终的 pass 列表可以通过
+/// [`crate::config::Config::all_passes`]获取
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone)]
+pub struct Passes {
+    /// 删除的 pass 名称
+    pub delete: Vec<String>,
+    /// 插入的 pass，值为`(插入位置, pass 名称)`，按顺序插入
+    pub insert: Vec<(usize, String)>,
+    /// 追加到末尾的 pass 名称
+    pub append: Vec<String>,
+}
+
+impl Passes {
+    /// 删除 pass
+    pub fn delete<S: ToString>(mut self, pass: S) -> Self {
+        self.delete.push(pass.to_string());
+        self
+    }
+
+    /// 在`idx`处插入 pass
+    pub fn insert<S: ToString>(mut self, idx: usize, pass: S) -> Self {
+        self.insert.push((idx, pass.to_string()));
+        self
+    }
+
+    /// 在末尾追加 pass
+    pub fn append<S: ToString>(mut self, pass: S) -> Self {
+        self.append.push(pass.to_string());
+        self
+    }
+
+    /// 是否没有任何修改
+    pub fn is_empty(&self) -> bool {
+        self.delete.is_empty() && self.insert.is_empty() && self.append.is_empty()
+    }
+}
+
+impl SetConfig for Passes {
+    const NAME: &'static str = "passes";
+
+    fn functions(&self) -> Vec<&'static str> {
+        let mut functions = vec![];
+        if !self.delete.is_empty() {
+            functions.push(PD_ConfigDeletePass::NAME);
+        }
+        if !self.insert.is_empty() {
+            functions.push(PD_ConfigInsertPass::NAME);
+        }
+        if !self.append.is_empty() {
+            functions.push(PD_ConfigAppendPass::NAME);
+        }
+        functions
+    }
+
+    fn set_to(self, runtime: &Runtime, config: *mut PD_Config) -> Result<()> {
+        let Passes {
+            delete,
+            insert,
+            append,
+        } = self;
+
+        for pass in delete {
+            let (_p, pass) = to_c_str(&pass);
+            try_call! { runtime => PD_ConfigDeletePass(config, pass) };
+        }
+
+        for (idx, pass) in insert {
+            let (_p, pass) = to_c_str(&pass);
+            try_call! { runtime => PD_ConfigInsertPass(config, idx, pass) };
+        }
+
+        for pass in append {
+            let (_p, pass) = to_c_str(&pass);
+            try_call! { runtime => PD_ConfigAppendPass(config, pass) };
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+//! 创建预测器时实际生效的配置
+
+use crate::common::OneDimArrayCstr;
+use crate::ctypes::{
+    Function, PD_Config, PD_ConfigAllPasses, PD_ConfigCudnnEnabled,
+    PD_ConfigFractionOfGpuMemoryForPool, PD_ConfigGetCpuMathLibraryNumThreads,
+    PD_ConfigGetModelDir, PD_ConfigGetParamsFile, PD_ConfigGetProgFile, PD_ConfigGlogInfoDisabled,
+    PD_ConfigGpuDeviceId, PD_ConfigIrOptim, PD_ConfigIsValid, PD_ConfigLiteEngineEnabled,
+    PD_ConfigMemoryOptimEnabled, PD_ConfigMemoryPoolInitSizeMb, PD_ConfigMkldnnBfloat16Enabled,
+    PD_ConfigMkldnnEnabled, PD_ConfigMkldnnQuantizerEnabled, PD_ConfigModelFromMemory,
+    PD_ConfigONNXRuntimeEnabled, PD_ConfigProfileEnabled, PD_ConfigShapeRangeInfoCollected,
+    PD_ConfigShapeRangeInfoPath, PD_ConfigSummary, PD_ConfigTensorRtDlaEnabled,
+    PD_ConfigTensorRtDynamicShapeEnabled, PD_ConfigTensorRtEngineEnabled,
+    PD_ConfigTensorRtOssEnabled, PD_ConfigThreadLocalStreamEnabled,
+    PD_ConfigTrtAllowBuildAtRuntime, PD_ConfigTunedTensorRtDynamicShape, PD_ConfigUseFcPadding,
+    PD_ConfigUseGpu, PD_ConfigUseXpu, PD_ConfigXpuDeviceId, PD_CstrDestroy,
+};
+use crate::runtime::Runtime;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_void};
+
+extern "C" {
+    fn free(ptr: *mut c_void);
+}
+
+/// 创建[`crate::Predictor`]时通过`PD_Config*`查询函数读取的实际生效的配置
+///
+/// 各字段在动态库中不存在对应的查询函数时为`None`
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigReport {
+    /// 非Combined 模型所在目录
+    pub model_dir: Option<String>,
+    /// Combined 模型文件路径
+    pub prog_file: Option<String>,
+    /// Combined 模型参数文件路径
+    pub params_file: Option<String>,
+    /// 是否从内存中加载模型
+    pub model_from_memory: Option<bool>,
+
+    /// 是否使用 GPU
+    pub use_gpu: Option<bool>,
+    /// GPU 设备id
+    pub gpu_device_id: Option<i32>,
+    /// 初始化分配的gpu显存，以MB为单位
+    pub memory_pool_init_size_mb: Option<i32>,
+    /// 初始化显存占总显存的百分比
+    pub fraction_of_gpu_memory_for_pool: Option<f32>,
+    /// 是否启用 CUDNN
+    pub cudnn: Option<bool>,
+    /// 是否启用线程流
+    pub thread_local_stream: Option<bool>,
+    /// 是否启用 TensorRT
+    pub tensor_rt: Option<bool>,
+    /// 是否启用 TensorRT 动态 shape
+    pub tensor_rt_dynamic_shape: Option<bool>,
+    /// 是否使用收集的 shape 范围作为 TensorRT 动态 shape
+    pub tensor_rt_tuned_dynamic_shape: Option<bool>,
+    /// 输入 shape 超出收集的范围时是否允许在运行时重新构建 TensorRT 引擎
+    pub tensor_rt_allow_build_at_runtime: Option<bool>,
+    /// 是否启用 TensorRT OSS
+    pub tensor_rt_oss: Option<bool>,
+    /// 是否启用 TensorRT DLA
+    pub tensor_rt_dla: Option<bool>,
+
+    /// 是否使用 XPU
+    pub use_xpu: Option<bool>,
+    /// XPU 设备id
+    pub xpu_device_id: Option<i32>,
+    /// 是否启用 ONNXRuntime
+    pub onnx_runtime: Option<bool>,
+    /// 是否启用 Lite 子图
+    pub lite_engine: Option<bool>,
+
+    /// CPU Blas 库计算线程数
+    pub cpu_math_library_num_threads: Option<i32>,
+    /// 是否启用 MKLDNN
+    pub mkldnn: Option<bool>,
+    /// 是否启用 MKLDNN 量化
+    pub mkldnn_quantizer: Option<bool>,
+    /// 是否启用 MKLDNN bfloat16
+    pub mkldnn_bfloat16: Option<bool>,
+
+    /// 是否启用 IR 优化
+    pub ir_optimization: Option<bool>,
+    /// 是否启用内存/显存复用
+    pub memory_optimization: Option<bool>,
+    /// 是否启用 FC Padding
+    pub fc_padding: Option<bool>,
+    /// 是否启用性能分析
+    pub profile: Option<bool>,
+    /// 是否禁用 glog 日志
+    pub glog_info_disabled: Option<bool>,
+    /// 是否收集运行时输入的 shape 范围
+    pub shape_range_info_collected: Option<bool>,
+    /// shape 范围文件路径
+    pub shape_range_info_path: Option<String>,
+    /// 配置是否有效
+    pub valid: Option<bool>,
+
+    /// IR 优化的 pass 列表
+    pub passes: Option<Vec<String>>,
+
+    /// `PD_ConfigSummary`返回的配置摘要
+    pub summary: Option<String>,
+}
+
+impl ConfigReport {
+    /// 从`config`中读取配置，不会修改或释放`config`
+    pub(crate) fn read(runtime: &Runtime, config: *mut PD_Config) -> Self {
+        macro_rules! get {
+            ($name: ident) => {
+                $name::load(runtime).ok().map(|f| unsafe { f(config) })
+            };
+        }
+
+        let string = |ptr: Option<*const c_char>| {
+            ptr.filter(|p| !p.is_null())
+                .map(|p| unsafe { CStr::from_ptr(p) }.to_string_lossy().to_string())
+        };
+
+        // `PD_ConfigShapeRangeInfoPath`返回通过`malloc`复制的字符串，需要手动释放
+        let shape_range_info_path = get!(PD_ConfigShapeRangeInfoPath)
+            .filter(|p| !p.is_null())
+            .map(|p| {
+                let s = string(Some(p)).unwrap_or_default();
+                unsafe { free(p as *mut c_void) };
+                s
+            });
+
+        let passes = get!(PD_ConfigAllPasses)
+            .filter(|p| !p.is_null())
+            .map(|p| OneDimArrayCstr::from_ptr(runtime, p).into());
+
+        let summary = get!(PD_ConfigSummary).filter(|p| !p.is_null()).map(|p| {
+            let s = string(Some(unsafe { (*p).data } as *const c_char)).unwrap_or_default();
+            if let Ok(destroy) = PD_CstrDestroy::load(runtime) {
+                unsafe { destroy(p) };
+            }
+            s
+        });
+
+        Self {
+            model_dir: string(get!(PD_ConfigGetModelDir)),
+            prog_file: string(get!(PD_ConfigGetProgFile)),
+            params_file: string(get!(PD_ConfigGetParamsFile)),
+            model_from_memory: get!(PD_ConfigModelFromMemory),
+            use_gpu: get!(PD_ConfigUseGpu),
+            gpu_device_id: get!(PD_ConfigGpuDeviceId),
+            memory_pool_init_size_mb: get!(PD_ConfigMemoryPoolInitSizeMb),
+            fraction_of_gpu_memory_for_pool: get!(PD_ConfigFractionOfGpuMemoryForPool),
+            cudnn: get!(PD_ConfigCudnnEnabled),
+            thread_local_stream: get!(PD_ConfigThreadLocalStreamEnabled),
+            tensor_rt: get!(PD_ConfigTensorRtEngineEnabled),
+            tensor_rt_dynamic_shape: get!(PD_ConfigTensorRtDynamicShapeEnabled),
+            tensor_rt_tuned_dynamic_shape: get!(PD_ConfigTunedTensorRtDynamicShape),
+            tensor_rt_allow_build_at_runtime: get!(PD_ConfigTrtAllowBuildAtRuntime),
+            tensor_rt_oss: get!(PD_ConfigTensorRtOssEnabled),
+            tensor_rt_dla: get!(PD_ConfigTensorRtDlaEnabled),
+            use_xpu: get!(PD_ConfigUseXpu),
+            xpu_device_id: get!(PD_ConfigXpuDeviceId),
+            onnx_runtime: get!(PD_ConfigONNXRuntimeEnabled),
+            lite_engine: get!(PD_ConfigLiteEngineEnabled),
+            cpu_math_library_num_threads: get!(PD_ConfigGetCpuMathLibraryNumThreads),
+            mkldnn: get!(PD_ConfigMkldnnEnabled),
+            mkldnn_quantizer: get!(PD_ConfigMkldnnQuantizerEnabled),
+            mkldnn_bfloat16: get!(PD_ConfigMkldnnBfloat16Enabled),
+            ir_optimization: get!(PD_ConfigIrOptim),
+            memory_optimization: get!(PD_ConfigMemoryOptimEnabled),
+            fc_padding: get!(PD_ConfigUseFcPadding),
+            profile: get!(PD_ConfigProfileEnabled),
+            glog_info_disabled: get!(PD_ConfigGlogInfoDisabled),
+            shape_range_info_collected: get!(PD_ConfigShapeRangeInfoCollected),
+            shape_range_info_path,
+            valid: get!(PD_ConfigIsValid),
+            passes,
+            summary,
+        }
+    }
+}
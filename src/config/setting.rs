@@ -1,14 +1,17 @@
-use crate::call;
 use crate::common::PrecisionType;
 use crate::config::SetConfig;
 use crate::ctypes::{
-    PD_Config, PD_ConfigEnableCudnn, PD_ConfigEnableGpuMultiStream, PD_ConfigEnableMkldnnBfloat16,
-    PD_ConfigEnableONNXRuntime, PD_ConfigEnableORTOptimization, PD_ConfigEnableTensorRtDla,
-    PD_ConfigEnableTensorRtEngine, PD_ConfigEnableTensorRtOSS, PD_ConfigEnableUseGpu,
+    Function, PD_Config, PD_ConfigEnableCudnn, PD_ConfigEnableGpuMultiStream,
+    PD_ConfigEnableMKLDNN, PD_ConfigEnableMkldnnBfloat16, PD_ConfigEnableONNXRuntime,
+    PD_ConfigEnableORTOptimization, PD_ConfigEnableTensorRtDla, PD_ConfigEnableTensorRtEngine,
+    PD_ConfigEnableTensorRtOSS, PD_ConfigEnableTunedTensorRtDynamicShape, PD_ConfigEnableUseGpu,
     PD_ConfigEnableXpu, PD_ConfigSetBfloat16Op, PD_ConfigSetCpuMathLibraryNumThreads,
     PD_ConfigSetMkldnnCacheCapacity, PD_ConfigSetMkldnnOp, PD_ConfigSetTrtDynamicShapeInfo,
 };
+use crate::runtime::Runtime;
+use crate::try_call;
 use crate::utils::to_c_str;
+use crate::Result;
 use std::ptr::null;
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -108,6 +111,18 @@ pub struct TensorRT {
     pub enable_oss: bool,
     /// 启用TensorRT DLA进行预测加速. 值为DLA设备的id，可选0，1，...，DLA设备总数 - 1
     pub dla_core: Option<i32>,
+    /// 使用[`crate::config::Config::collect_shape_range_info`]收集的 shape 范围作为动态 Shape
+    pub tuned_dynamic_shape: Option<TunedDynamicShape>,
+}
+
+/// 使用收集的 shape 范围文件设置 TensorRT 的动态 Shape
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct TunedDynamicShape {
+    /// shape 范围文件路径
+    pub shape_range_info_path: String,
+    /// 输入 shape 超出收集的范围时是否允许在运行时重新构建 TensorRT 引擎
+    pub allow_build_at_runtime: bool,
 }
 
 /// TensorRT 的动态 Shape 信息
@@ -139,11 +154,34 @@ impl DynamicShapeInfo {
 }
 
 impl SetConfig for Cpu {
-    fn set_to(self, config: *mut PD_Config) {
+    const NAME: &'static str = "cpu";
+
+    fn functions(&self) -> Vec<&'static str> {
+        let mut functions = vec![];
+        if matches!(self.threads, Some(t) if t > 0) {
+            functions.push(PD_ConfigSetCpuMathLibraryNumThreads::NAME);
+        }
+        if let Some(mkldnn) = &self.mkldnn {
+            functions.push(PD_ConfigEnableMKLDNN::NAME);
+            if matches!(mkldnn.cache_size, Some(c) if c > 0) {
+                functions.push(PD_ConfigSetMkldnnCacheCapacity::NAME);
+            }
+            if mkldnn.op.is_some() {
+                functions.push(PD_ConfigSetMkldnnOp::NAME);
+            }
+            if mkldnn.op_f16.is_some() {
+                functions.push(PD_ConfigEnableMkldnnBfloat16::NAME);
+                functions.push(PD_ConfigSetBfloat16Op::NAME);
+            }
+        }
+        functions
+    }
+
+    fn set_to(self, runtime: &Runtime, config: *mut PD_Config) -> Result<()> {
         let Cpu { threads, mkldnn } = self;
         if let Some(t) = threads {
             if t > 0 {
-                call! { PD_ConfigSetCpuMathLibraryNumThreads(config, t) };
+                try_call! { runtime => PD_ConfigSetCpuMathLibraryNumThreads(config, t) };
             }
         }
         if let Some(Mkldnn {
@@ -152,28 +190,59 @@ impl SetConfig for Cpu {
             op_f16,
         }) = mkldnn
         {
+            try_call! { runtime => PD_ConfigEnableMKLDNN(config) };
             if let Some(cache) = cache_size {
                 if cache > 0 {
-                    call! { PD_ConfigSetMkldnnCacheCapacity(config, cache) };
+                    try_call! { runtime => PD_ConfigSetMkldnnCacheCapacity(config, cache) };
                 }
             }
             if let Some(op) = op {
                 let (_l, mut r): (Vec<_>, Vec<_>) = op.iter().map(|s| to_c_str(s)).unzip();
                 let size = r.len();
-                call! { PD_ConfigSetMkldnnOp(config, size, r.as_mut_ptr()) };
+                try_call! { runtime => PD_ConfigSetMkldnnOp(config, size, r.as_mut_ptr()) };
             }
             if let Some(op) = op_f16 {
-                call! { PD_ConfigEnableMkldnnBfloat16(config) };
+                try_call! { runtime => PD_ConfigEnableMkldnnBfloat16(config) };
                 let (_l, mut r): (Vec<_>, Vec<_>) = op.iter().map(|s| to_c_str(s)).unzip();
                 let size = r.len();
-                call! { PD_ConfigSetBfloat16Op(config, size, r.as_mut_ptr()) };
+                try_call! { runtime => PD_ConfigSetBfloat16Op(config, size, r.as_mut_ptr()) };
             }
         }
+
+        Ok(())
     }
 }
 
 impl SetConfig for Gpu {
-    fn set_to(self, config: *mut PD_Config) {
+    const NAME: &'static str = "gpu";
+
+    fn functions(&self) -> Vec<&'static str> {
+        let mut functions = vec![PD_ConfigEnableUseGpu::NAME];
+        if self.enable_multi_stream {
+            functions.push(PD_ConfigEnableGpuMultiStream::NAME);
+        }
+        if self.enable_cudnn {
+            functions.push(PD_ConfigEnableCudnn::NAME);
+        }
+        if let Some(trt) = &self.enable_tensor_rt {
+            functions.push(PD_ConfigEnableTensorRtEngine::NAME);
+            if !trt.dynamic_shape_info.is_empty() {
+                functions.push(PD_ConfigSetTrtDynamicShapeInfo::NAME);
+            }
+            if trt.enable_oss {
+                functions.push(PD_ConfigEnableTensorRtOSS::NAME);
+            }
+            if trt.dla_core.is_some() {
+                functions.push(PD_ConfigEnableTensorRtDla::NAME);
+            }
+            if trt.tuned_dynamic_shape.is_some() {
+                functions.push(PD_ConfigEnableTunedTensorRtDynamicShape::NAME);
+            }
+        }
+        functions
+    }
+
+    fn set_to(self, runtime: &Runtime, config: *mut PD_Config) -> Result<()> {
         let Gpu {
             memory_pool_init_size_mb,
             device_id,
@@ -182,12 +251,12 @@ impl SetConfig for Gpu {
             enable_tensor_rt,
         } = self;
 
-        call! { PD_ConfigEnableUseGpu(config, memory_pool_init_size_mb, device_id) };
+        try_call! { runtime => PD_ConfigEnableUseGpu(config, memory_pool_init_size_mb, device_id) };
         if enable_multi_stream {
-            call! { PD_ConfigEnableGpuMultiStream(config) };
+            try_call! { runtime => PD_ConfigEnableGpuMultiStream(config) };
         }
         if enable_cudnn {
-            call! { PD_ConfigEnableCudnn(config) };
+            try_call! { runtime => PD_ConfigEnableCudnn(config) };
         }
         if let Some(TensorRT {
             workspace_size,
@@ -200,10 +269,11 @@ impl SetConfig for Gpu {
             disable_plugin_fp16,
             enable_oss,
             dla_core,
+            tuned_dynamic_shape,
         }) = enable_tensor_rt
         {
-            call! {
-                PD_ConfigEnableTensorRtEngine(
+            try_call! {
+                runtime => PD_ConfigEnableTensorRtEngine(
                     config,
                     workspace_size,
                     max_batch_size,
@@ -240,8 +310,8 @@ impl SetConfig for Gpu {
                     optim_shapes.push(optim_shape.as_ptr() as *mut i32);
                 }
 
-                call! {
-                    PD_ConfigSetTrtDynamicShapeInfo(
+                try_call! {
+                    runtime => PD_ConfigSetTrtDynamicShapeInfo(
                         config,
                         tensor_num,
                         tensor_name.as_mut_ptr(),
@@ -255,18 +325,41 @@ impl SetConfig for Gpu {
             }
 
             if enable_oss {
-                call! { PD_ConfigEnableTensorRtOSS(config) };
+                try_call! { runtime => PD_ConfigEnableTensorRtOSS(config) };
             }
 
             if let Some(dla_core) = dla_core {
-                call! { PD_ConfigEnableTensorRtDla(config, dla_core) };
+                try_call! { runtime => PD_ConfigEnableTensorRtDla(config, dla_core) };
+            }
+
+            if let Some(TunedDynamicShape {
+                shape_range_info_path,
+                allow_build_at_runtime,
+            }) = tuned_dynamic_shape
+            {
+                let (_p, path) = to_c_str(&shape_range_info_path);
+                try_call! {
+                    runtime => PD_ConfigEnableTunedTensorRtDynamicShape(
+                        config,
+                        path,
+                        allow_build_at_runtime
+                    )
+                };
             }
         }
+
+        Ok(())
     }
 }
 
 impl SetConfig for Xpu {
-    fn set_to(self, config: *mut PD_Config) {
+    const NAME: &'static str = "xpu";
+
+    fn functions(&self) -> Vec<&'static str> {
+        vec![PD_ConfigEnableXpu::NAME]
+    }
+
+    fn set_to(self, runtime: &Runtime, config: *mut PD_Config) -> Result<()> {
         let Xpu {
             l3_workspace_size,
             locked,
@@ -281,8 +374,8 @@ impl SetConfig for Xpu {
             .unwrap_or_else(|| (None, null()));
         let (_p, p) = to_c_str(&precision);
 
-        call! {
-            PD_ConfigEnableXpu(
+        try_call! {
+            runtime => PD_ConfigEnableXpu(
                 config,
                 l3_workspace_size,
                 locked,
@@ -292,14 +385,28 @@ impl SetConfig for Xpu {
                 adaptive_seqlen
             )
         };
+
+        Ok(())
     }
 }
 
 impl SetConfig for ONNXRuntime {
-    fn set_to(self, config: *mut PD_Config) {
-        call! { PD_ConfigEnableONNXRuntime(config) };
+    const NAME: &'static str = "onnx_runtime";
+
+    fn functions(&self) -> Vec<&'static str> {
+        let mut functions = vec![PD_ConfigEnableONNXRuntime::NAME];
         if self.enable_optimization {
-            call! { PD_ConfigEnableORTOptimization(config) };
+            functions.push(PD_ConfigEnableORTOptimization::NAME);
         }
+        functions
+    }
+
+    fn set_to(self, runtime: &Runtime, config: *mut PD_Config) -> Result<()> {
+        try_call! { runtime => PD_ConfigEnableONNXRuntime(config) };
+        if self.enable_optimization {
+            try_call! { runtime => PD_ConfigEnableORTOptimization(config) };
+        }
+
+        Ok(())
     }
 }
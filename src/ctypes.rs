@@ -4,46 +4,53 @@
 #![allow(rustdoc::broken_intra_doc_links)]
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
-#![allow(deref_nullptr)]
 
+use crate::error::Result;
+use crate::runtime::Runtime;
 use libloading::Symbol;
 
 pub type PD_Bool = bool;
 
 /// Tensor 的数据精度, 默认值为[`DataType::Float32`]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
 #[repr(i32)]
 pub enum DataType {
     Unknown = -1,
+    #[default]
     Float32,
     Int32,
     Int64,
     Uint8,
+    Int8,
 }
 
-impl Default for DataType {
-    fn default() -> Self {
-        Self::Float32
+impl DataType {
+    /// 单个元素占用的字节数，[`DataType::Unknown`]为 0
+    pub fn size(self) -> usize {
+        use std::mem::size_of;
+        match self {
+            DataType::Unknown => 0,
+            DataType::Float32 => size_of::<f32>(),
+            DataType::Int32 => size_of::<i32>(),
+            DataType::Int64 => size_of::<i64>(),
+            DataType::Uint8 => size_of::<u8>(),
+            DataType::Int8 => size_of::<i8>(),
+        }
     }
 }
 
 /// 模型的运行精度, 默认值为[`PrecisionType::Float32`]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
 #[repr(i32)]
 pub enum PrecisionType {
+    #[default]
     Float32 = 0,
     Int8,
     Half,
 }
 
-impl Default for PrecisionType {
-    fn default() -> Self {
-        Self::Float32
-    }
-}
-
 /// 目标设备硬件类型，用户可以根据应用场景选择硬件平台类型
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
@@ -78,7 +85,7 @@ fn bindgen_test_layout_PD_OneDimArrayInt32() {
         concat!("Alignment of ", stringify!(PD_OneDimArrayInt32))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<PD_OneDimArrayInt32>())).size as *const _ as usize },
+        ::std::mem::offset_of!(PD_OneDimArrayInt32, size),
         0usize,
         concat!(
             "Offset of field: ",
@@ -88,7 +95,7 @@ fn bindgen_test_layout_PD_OneDimArrayInt32() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<PD_OneDimArrayInt32>())).data as *const _ as usize },
+        ::std::mem::offset_of!(PD_OneDimArrayInt32, data),
         8usize,
         concat!(
             "Offset of field: ",
@@ -117,7 +124,7 @@ fn bindgen_test_layout_PD_OneDimArraySize() {
         concat!("Alignment of ", stringify!(PD_OneDimArraySize))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<PD_OneDimArraySize>())).size as *const _ as usize },
+        ::std::mem::offset_of!(PD_OneDimArraySize, size),
         0usize,
         concat!(
             "Offset of field: ",
@@ -127,7 +134,7 @@ fn bindgen_test_layout_PD_OneDimArraySize() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<PD_OneDimArraySize>())).data as *const _ as usize },
+        ::std::mem::offset_of!(PD_OneDimArraySize, data),
         8usize,
         concat!(
             "Offset of field: ",
@@ -156,7 +163,7 @@ fn bindgen_test_layout_PD_OneDimArrayCstr() {
         concat!("Alignment of ", stringify!(PD_OneDimArrayCstr))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<PD_OneDimArrayCstr>())).size as *const _ as usize },
+        ::std::mem::offset_of!(PD_OneDimArrayCstr, size),
         0usize,
         concat!(
             "Offset of field: ",
@@ -166,7 +173,7 @@ fn bindgen_test_layout_PD_OneDimArrayCstr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<PD_OneDimArrayCstr>())).data as *const _ as usize },
+        ::std::mem::offset_of!(PD_OneDimArrayCstr, data),
         8usize,
         concat!(
             "Offset of field: ",
@@ -195,7 +202,7 @@ fn bindgen_test_layout_PD_Cstr() {
         concat!("Alignment of ", stringify!(PD_Cstr))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<PD_Cstr>())).size as *const _ as usize },
+        ::std::mem::offset_of!(PD_Cstr, size),
         0usize,
         concat!(
             "Offset of field: ",
@@ -205,7 +212,7 @@ fn bindgen_test_layout_PD_Cstr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<PD_Cstr>())).data as *const _ as usize },
+        ::std::mem::offset_of!(PD_Cstr, data),
         8usize,
         concat!(
             "Offset of field: ",
@@ -234,7 +241,7 @@ fn bindgen_test_layout_PD_TwoDimArraySize() {
         concat!("Alignment of ", stringify!(PD_TwoDimArraySize))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<PD_TwoDimArraySize>())).size as *const _ as usize },
+        ::std::mem::offset_of!(PD_TwoDimArraySize, size),
         0usize,
         concat!(
             "Offset of field: ",
@@ -244,7 +251,7 @@ fn bindgen_test_layout_PD_TwoDimArraySize() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<PD_TwoDimArraySize>())).data as *const _ as usize },
+        ::std::mem::offset_of!(PD_TwoDimArraySize, data),
         8usize,
         concat!(
             "Offset of field: ",
@@ -264,17 +271,26 @@ pub trait Function {
     const NAME: &'static str;
     type Type;
 
+    /// 从默认动态库中获取函数，动态库加载失败或函数不存在时 panic
     fn get() -> Symbol<'static, Self::Type> {
-        unsafe {
-            crate::LIBRARY
-                .get(Self::NAME.as_bytes())
-                .unwrap_or_else(|_| panic!("can't get symbol {}", Self::NAME))
-        }
+        Self::try_get().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// 从默认动态库中获取函数
+    fn try_get() -> Result<Symbol<'static, Self::Type>> {
+        Runtime::default_runtime()?.get::<Self>()
+    }
+
+    /// 从指定的动态库中获取函数
+    fn load(runtime: &Runtime) -> Result<Symbol<'_, Self::Type>> {
+        runtime.get::<Self>()
     }
 }
 
 macro_rules! extern_fn {
     (
+        $( #[doc = $list_doc: literal] )*
+        pub const $list: ident;
         $(
             extern "C" {
                 $( #[doc = $doc: literal] )*
@@ -282,6 +298,9 @@ macro_rules! extern_fn {
             }
         )*
     ) => {
+        $( #[doc = $list_doc] )*
+        pub const $list: &[&str] = &[ $( stringify!($name) ),* ];
+
         $(
             $( #[doc = $doc] )*
             pub struct $name;
@@ -293,7 +312,14 @@ macro_rules! extern_fn {
     };
 }
 
+/// 所有已声明函数的名称
+pub fn function_names() -> impl Iterator<Item = &'static str> {
+    CONFIG_FUNCTIONS.iter().chain(PREDICTOR_FUNCTIONS).copied()
+}
+
 extern_fn! {
+    /// `PD_Config*`相关函数的名称
+    pub const CONFIG_FUNCTIONS;
 
     extern "C" {
         #[doc = ""]
@@ -1161,6 +1187,8 @@ pub struct PD_Tensor {
 }
 
 extern_fn! {
+    /// `PD_Predictor*`、`PD_Tensor*`及其它函数的名称
+    pub const PREDICTOR_FUNCTIONS;
 
     extern "C" {
         #[doc = ""]
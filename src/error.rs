@@ -0,0 +1,97 @@
+//! 错误类型
+
+use crate::common::DataType;
+use std::fmt::{Display, Formatter};
+
+/// 本库中所有可失败操作返回的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// 加载`paddle_inference_c`动态库失败
+    LibraryLoad {
+        /// 动态库路径或名称
+        path: String,
+        /// 失败原因
+        reason: String,
+    },
+    /// 动态库中不存在指定的函数
+    MissingSymbol(&'static str),
+    /// C 接口返回了空指针，值为返回空指针的函数名称
+    NullHandle(&'static str),
+    /// 动态库不支持指定的配置
+    Unsupported {
+        /// 配置名称
+        option: &'static str,
+        /// 设置该配置需要但动态库中不存在的函数
+        function: &'static str,
+    },
+    /// Tensor 的数据类型与输入/输出数据的类型不匹配
+    DataTypeMismatch {
+        /// 期望的数据类型
+        expected: DataType,
+        /// Tensor 实际的数据类型
+        actual: DataType,
+    },
+    /// 数据长度与 Tensor 的 shape 不匹配
+    ShapeMismatch {
+        /// Tensor 的 shape
+        shape: Vec<i32>,
+        /// 数据长度
+        len: usize,
+    },
+    /// Tensor 的 shape 包含负数(尚未推导的维度)或元素数量溢出，值为该 shape
+    InvalidShape(Vec<i32>),
+    /// 模型中不存在指定名称的输入/输出 Tensor
+    TensorNotFound(String),
+    /// Tensor 的数据类型未知，通常是尚未写入数据，值为 Tensor 名称
+    UnknownDataType(String),
+    /// 等待预测的任务数量已达到队列上限
+    QueueFull,
+    /// 执行预测的工作线程已停止
+    WorkerStopped,
+    /// 执行模型预测失败
+    RunFailed,
+    /// 读写文件失败，值为失败原因
+    Io(String),
+    /// `.npy`/`.npz`文件的格式错误或包含不支持的数据类型，值为失败原因
+    InvalidNpy(String),
+    /// LoD 信息无效，值为失败原因
+    InvalidLod(String),
+    /// profile 报告的格式错误，值为失败原因
+    InvalidProfile(String),
+}
+
+/// `Result<T, paddle_inference::Error>`的简写
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::LibraryLoad { path, reason } => {
+                write!(f, "加载动态库`{}`失败: {}", path, reason)
+            }
+            Error::MissingSymbol(name) => write!(f, "动态库中不存在函数`{}`", name),
+            Error::NullHandle(name) => write!(f, "`{}`返回了空指针", name),
+            Error::Unsupported { option, function } => {
+                write!(f, "动态库不支持配置`{}`: 不存在函数`{}`", option, function)
+            }
+            Error::DataTypeMismatch { expected, actual } => {
+                write!(f, "数据类型不匹配: 期望{:?}, 实际为{:?}", expected, actual)
+            }
+            Error::ShapeMismatch { shape, len } => {
+                write!(f, "数据长度{}与shape {:?}不匹配", len, shape)
+            }
+            Error::InvalidShape(shape) => write!(f, "shape {:?}无效", shape),
+            Error::TensorNotFound(name) => write!(f, "模型中不存在Tensor`{}`", name),
+            Error::UnknownDataType(name) => write!(f, "Tensor`{}`的数据类型未知", name),
+            Error::QueueFull => write!(f, "预测队列已满"),
+            Error::WorkerStopped => write!(f, "预测工作线程已停止"),
+            Error::RunFailed => write!(f, "执行模型预测失败"),
+            Error::Io(reason) => write!(f, "读写文件失败: {}", reason),
+            Error::InvalidNpy(reason) => write!(f, "npy 文件格式错误: {}", reason),
+            Error::InvalidLod(reason) => write!(f, "LoD 信息无效: {}", reason),
+            Error::InvalidProfile(reason) => write!(f, "profile 报告格式错误: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,164 @@
+//! 主机内存中的 Tensor
+
+use crate::common::DataType;
+use crate::tensor::Element;
+use crate::{Error, Result};
+
+/// 预测的输入或输出，值为`(名称, 数据)`
+pub type NamedTensors = Vec<(String, HostTensor)>;
+
+/// [`HostTensor`]的数据
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum HostData {
+    Float32(Vec<f32>),
+    Int64(Vec<i64>),
+    Int32(Vec<i32>),
+    Uint8(Vec<u8>),
+    Int8(Vec<i8>),
+}
+
+impl HostData {
+    /// 数据类型
+    pub fn data_type(&self) -> DataType {
+        match self {
+            HostData::Float32(_) => DataType::Float32,
+            HostData::Int64(_) => DataType::Int64,
+            HostData::Int32(_) => DataType::Int32,
+            HostData::Uint8(_) => DataType::Uint8,
+            HostData::Int8(_) => DataType::Int8,
+        }
+    }
+
+    /// 元素数量
+    pub fn len(&self) -> usize {
+        match self {
+            HostData::Float32(v) => v.len(),
+            HostData::Int64(v) => v.len(),
+            HostData::Int32(v) => v.len(),
+            HostData::Uint8(v) => v.len(),
+            HostData::Int8(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 拥有数据的主机内存 Tensor，包含 shape、数据类型及数据
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostTensor {
+    shape: Vec<usize>,
+    data: HostData,
+}
+
+impl HostTensor {
+    /// 创建 Tensor，`data`的长度与`shape`之积不相等时返回[`Error::ShapeMismatch`]
+    pub fn new<T: Element>(shape: Vec<usize>, data: Vec<T>) -> Result<Self> {
+        Self::from_data(shape, T::into_host_data(data))
+    }
+
+    /// 使用[`HostData`]创建 Tensor，检查规则同[`Self::new`]，`shape`之积溢出时同样返回[`Error::ShapeMismatch`]
+    pub fn from_data(shape: Vec<usize>, data: HostData) -> Result<Self> {
+        if element_count(&shape) != Some(data.len()) {
+            return Err(Error::ShapeMismatch {
+                shape: shape.iter().map(|&s| s as i32).collect(),
+                len: data.len(),
+            });
+        }
+        Ok(Self { shape, data })
+    }
+
+    /// 创建所有元素均为默认值的 Tensor，`shape`之积溢出时返回[`Error::InvalidShape`]
+    pub fn zeros<T: Element>(shape: Vec<usize>) -> Result<Self> {
+        let len = element_count(&shape)
+            .ok_or_else(|| Error::InvalidShape(shape.iter().map(|&s| s as i32).collect()))?;
+        Ok(Self {
+            shape,
+            data: T::into_host_data(vec![T::default(); len]),
+        })
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    pub fn data_type(&self) -> DataType {
+        self.data.data_type()
+    }
+
+    pub fn data(&self) -> &HostData {
+        &self.data
+    }
+
+    /// 元素数量
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// 获取数据，`T`与[`Self::data_type`]不匹配时返回`None`
+    pub fn as_slice<T: Element>(&self) -> Option<&[T]> {
+        T::host_data_slice(&self.data)
+    }
+
+    /// 获取可修改的数据，`T`与[`Self::data_type`]不匹配时返回`None`
+    pub fn as_mut_slice<T: Element>(&mut self) -> Option<&mut [T]> {
+        T::host_data_slice_mut(&mut self.data)
+    }
+
+    /// 分解为 shape 及数据
+    pub fn into_parts(self) -> (Vec<usize>, HostData) {
+        (self.shape, self.data)
+    }
+
+    /// 转换为数据，`T`与[`Self::data_type`]不匹配时返回[`Error::DataTypeMismatch`]
+    pub fn into_vec<T: Element>(self) -> Result<Vec<T>> {
+        let actual = self.data_type();
+        T::from_host_data(self.data).ok_or(Error::DataTypeMismatch {
+            expected: T::DATA_TYPE,
+            actual,
+        })
+    }
+}
+
+/// `shape`之积，溢出时返回`None`
+pub(crate) fn element_count(shape: &[usize]) -> Option<usize> {
+    shape.iter().try_fold(1usize, |s, &d| s.checked_mul(d))
+}
+
+#[test]
+fn test_host_tensor() {
+    let t = HostTensor::new(vec![2, 2], vec![1.0f32, 2.0, 3.0, 4.0]).unwrap();
+    assert_eq!(t.shape(), [2, 2]);
+    assert_eq!(t.data_type(), DataType::Float32);
+    assert_eq!(t.as_slice::<f32>(), Some(&[1.0, 2.0, 3.0, 4.0][..]));
+    assert_eq!(t.as_slice::<i32>(), None);
+    assert_eq!(
+        t.clone().into_vec::<i64>(),
+        Err(Error::DataTypeMismatch {
+            expected: DataType::Int64,
+            actual: DataType::Float32
+        })
+    );
+    assert_eq!(t.into_vec::<f32>().unwrap().len(), 4);
+
+    assert_eq!(
+        HostTensor::new(vec![3], vec![1u8]),
+        Err(Error::ShapeMismatch {
+            shape: vec![3],
+            len: 1
+        })
+    );
+    assert!(HostTensor::new(vec![usize::MAX, 2, 0], Vec::<f32>::new()).is_err());
+    assert_eq!(HostTensor::zeros::<i8>(vec![2, 3]).unwrap().len(), 6);
+    assert!(matches!(
+        HostTensor::zeros::<f32>(vec![usize::MAX, 2]),
+        Err(Error::InvalidShape(_))
+    ));
+}
@@ -1,38 +1,77 @@
 #![doc = include_str!("../README.md")]
+// `call!`/`try_call!`需要在 unsafe 块中展开参数表达式
+#![allow(clippy::macro_metavars_in_unsafe)]
 #[cfg(feature = "serde")]
 #[cfg_attr(feature = "serde", macro_use)]
 extern crate serde;
 
+/// 启用`tracing`特性时创建`INFO`级别的 span 并进入到当前作用域结束，否则不做任何事
+///
+/// 参数与`tracing::info_span!`相同，字段的值仅在 span 启用时计算
+macro_rules! trace_span {
+    ($($args: tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!($($args)*).entered();
+    };
+}
+
+#[cfg(feature = "ndarray")]
+mod array;
+#[cfg(feature = "async")]
+mod async_predictor;
+mod batch;
+mod bench;
 pub mod common;
 pub mod config;
 pub mod ctypes;
+mod error;
+mod host;
+mod lod;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod npy;
+mod pool;
 mod predictor;
+mod profile;
+pub mod runtime;
 mod tensor;
 pub mod utils;
 
-use libloading::{library_filename, Library};
-use once_cell::sync::Lazy;
-pub use predictor::Predictor;
-pub use tensor::Tensor;
-
-static LIBRARY: Lazy<Library> = Lazy::new(|| unsafe {
-    Library::new(library_filename("paddle_inference_c")).expect("can't not load paddle_inference_c")
-});
+#[cfg(feature = "async")]
+pub use async_predictor::AsyncPredictor;
+pub use batch::{BatchOptions, Batcher, CropDim};
+pub use bench::{benchmark, BenchInput, BenchOptions, BenchReport, Latency};
+pub use error::{Error, Result};
+pub use host::{HostData, HostTensor, NamedTensors};
+pub use lod::Lod;
+pub use pool::{PredictorGuard, PredictorPool};
+pub use predictor::{Predictor, ShrinkPolicy};
+pub use profile::{OpDiff, OpProfile, ProfileReport, ProfileSortKey};
+pub use runtime::Runtime;
+pub use tensor::{Element, Tensor};
 
 /// 用于快速调用实现了[`ctypes::Function`]的对象
 ///
+/// **注意：** 动态库加载失败或函数不存在时会 panic，需要返回错误时请使用[`try_call`]
+///
 /// **使用方法：**
 ///
 /// ``` no_run
-/// use paddle_inference::call;
+/// use paddle_inference::{call, Runtime};
 /// use paddle_inference::ctypes::{Function, PD_ConfigCreate, PD_ConfigDestroy};
 ///
+/// // 使用默认动态库
 /// let ptr = call!(PD_ConfigCreate());
 /// call!(PD_ConfigDestroy(ptr));
 ///
 /// /// 等同于
 /// let ptr = unsafe { PD_ConfigCreate::get()() };
 /// unsafe { PD_ConfigDestroy::get()(ptr) };
+///
+/// // 使用指定的动态库
+/// let runtime = Runtime::load("libpaddle_inference_c.so").unwrap();
+/// let ptr = call!(&runtime => PD_ConfigCreate());
+/// call!(&runtime => PD_ConfigDestroy(ptr));
 /// ```
 #[macro_export]
 macro_rules! call {
@@ -41,4 +80,38 @@ macro_rules! call {
             <$name as $crate::ctypes::Function>::get()( $($args),* )
         }
     };
+    ($runtime: expr => $name: ident ( $( $args: expr ),* )) => {
+        unsafe {
+            <$name as $crate::ctypes::Function>::load($runtime)
+                .unwrap_or_else(|e| panic!("{}", e))( $($args),* )
+        }
+    };
+}
+
+/// 与[`call`]相同，但在动态库加载失败或函数不存在时通过`?`返回[`Error`]
+///
+/// **使用方法：**
+///
+/// ``` no_run
+/// use paddle_inference::try_call;
+/// use paddle_inference::ctypes::{Function, PD_ConfigCreate, PD_ConfigDestroy};
+///
+/// fn create(runtime: &paddle_inference::Runtime) -> paddle_inference::Result<()> {
+///     let ptr = try_call!(runtime => PD_ConfigCreate());
+///     try_call!(runtime => PD_ConfigDestroy(ptr));
+///     Ok(())
+/// }
+/// ```
+#[macro_export]
+macro_rules! try_call {
+    ($name: ident ( $( $args: expr ),* )) => {
+        unsafe {
+            <$name as $crate::ctypes::Function>::try_get()?( $($args),* )
+        }
+    };
+    ($runtime: expr => $name: ident ( $( $args: expr ),* )) => {
+        unsafe {
+            <$name as $crate::ctypes::Function>::load($runtime)?( $($args),* )
+        }
+    };
 }
@@ -0,0 +1,135 @@
+//! 变长序列的 LoD 信息
+
+use crate::{Error, Result};
+
+/// LoD（Level of Details）信息，每一层为一组偏移量，用于在第一维上划分变长序列
+///
+/// 例如`[[0, 2, 5]]`表示第一维的`0..2`及`2..5`分别为两个序列。多层 LoD 中，每一层的最后一个偏移量等于下一层的序列数量
+///
+/// ```
+/// use paddle_inference::Lod;
+///
+/// let (data, lod) = Lod::pack(&[vec![1i64, 2], vec![3, 4, 5]]);
+/// assert_eq!(data, [1, 2, 3, 4, 5]);
+/// assert_eq!(lod.levels(), [vec![0, 2, 5]]);
+/// assert!(Lod::new(vec![vec![0, 3, 2]]).is_err());
+/// ```
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "Vec<Vec<usize>>", into = "Vec<Vec<usize>>")
+)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Lod(Vec<Vec<usize>>);
+
+impl Lod {
+    /// 创建 LoD，以下情况返回[`Error::InvalidLod`]
+    /// - 某一层为空或第一个偏移量不为`0`
+    /// - 某一层的偏移量递减
+    /// - 某一层的最后一个偏移量不等于下一层的序列数量
+    pub fn new(levels: Vec<Vec<usize>>) -> Result<Self> {
+        for (i, level) in levels.iter().enumerate() {
+            if level.first() != Some(&0) {
+                return Err(Error::InvalidLod(format!("第{}层需要以`0`开始", i)));
+            }
+            if level.windows(2).any(|w| w[0] > w[1]) {
+                return Err(Error::InvalidLod(format!("第{}层的偏移量递减", i)));
+            }
+            if let Some(next) = levels.get(i + 1) {
+                let last = level[level.len() - 1];
+                if last != next.len().saturating_sub(1) {
+                    return Err(Error::InvalidLod(format!(
+                        "第{}层的最后一个偏移量{}与第{}层的序列数量{}不相等",
+                        i,
+                        last,
+                        i + 1,
+                        next.len().saturating_sub(1)
+                    )));
+                }
+            }
+        }
+        Ok(Self(levels))
+    }
+
+    /// 将变长序列拼接为一维数据，并创建对应的单层 LoD
+    pub fn pack<T: Clone, S: AsRef<[T]>>(sequences: &[S]) -> (Vec<T>, Self) {
+        let mut data = vec![];
+        let mut offsets = vec![0];
+        for sequence in sequences {
+            data.extend_from_slice(sequence.as_ref());
+            offsets.push(data.len());
+        }
+        (data, Self(vec![offsets]))
+    }
+
+    /// 每一层的偏移量
+    pub fn levels(&self) -> &[Vec<usize>] {
+        &self.0
+    }
+
+    /// LoD 的层数
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// 检查最后一层的最后一个偏移量是否等于第一维的大小`batch`，空 LoD 总是通过检查
+    pub fn check(&self, batch: usize) -> Result<()> {
+        match self.0.last().and_then(|level| level.last()) {
+            Some(&last) if last != batch => Err(Error::InvalidLod(format!(
+                "最后一个偏移量{}与第一维的大小{}不相等",
+                last, batch
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// 最后一层中每个序列在第一维上的范围
+    pub fn sequences(&self) -> impl Iterator<Item = std::ops::Range<usize>> + '_ {
+        self.0
+            .last()
+            .into_iter()
+            .flat_map(|level| level.windows(2).map(|w| w[0]..w[1]))
+    }
+
+    /// 由动态库返回的偏移量创建，不进行检查
+    pub(crate) fn from_raw(levels: Vec<Vec<usize>>) -> Self {
+        Self(levels)
+    }
+}
+
+impl TryFrom<Vec<Vec<usize>>> for Lod {
+    type Error = Error;
+
+    fn try_from(levels: Vec<Vec<usize>>) -> Result<Self> {
+        Self::new(levels)
+    }
+}
+
+impl From<Lod> for Vec<Vec<usize>> {
+    fn from(lod: Lod) -> Self {
+        lod.0
+    }
+}
+
+#[test]
+fn test_lod() {
+    assert!(Lod::new(vec![]).unwrap().is_empty());
+    let lod = Lod::new(vec![vec![0, 2, 3], vec![0, 1, 4, 6]]).unwrap();
+    assert_eq!(lod.len(), 2);
+    assert!(lod.check(6).is_ok());
+    assert!(lod.check(5).is_err());
+    assert_eq!(lod.sequences().collect::<Vec<_>>(), [0..1, 1..4, 4..6]);
+
+    assert!(Lod::new(vec![vec![]]).is_err());
+    assert!(Lod::new(vec![vec![1, 2]]).is_err());
+    assert!(Lod::new(vec![vec![0, 2, 1]]).is_err());
+    assert!(Lod::new(vec![vec![0, 3], vec![0, 1, 2]]).is_err());
+
+    let (data, lod) = Lod::pack::<u8, &[u8]>(&[]);
+    assert!(data.is_empty());
+    assert_eq!(lod.levels(), [vec![0]]);
+}
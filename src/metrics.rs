@@ -0,0 +1,162 @@
+//! Prometheus 指标，需要启用`metrics`特性
+//!
+//! 所有指标注册在[`registry`]中，均包含`model`标签，值为[`crate::config::Config::model_name`]：
+//! - `paddle_inference_runs_total`：[`crate::Predictor::run`]的调用次数
+//! - `paddle_inference_run_failures_total`：预测失败的次数
+//! - `paddle_inference_run_duration_seconds`：单次预测的耗时
+//! - `paddle_inference_batch_size`：第一个输入第一维的大小
+//! - `paddle_inference_input_bytes`：所有输入占用的字节数
+//! - `paddle_inference_copy_duration_seconds`：[`crate::Tensor::copy_from`]/[`crate::Tensor::copy_to`]
+//!   单次复制数据的耗时，`direction`标签为`input`或`output`，其他复制方法均通过二者复制数据
+//!
+//! 可以通过[`gather`]获取文本格式的指标，或通过[`serve`]启动简单的 HTTP 接口供 Prometheus 抓取
+
+use crate::{Error, Result};
+use once_cell::sync::Lazy;
+use prometheus::{
+    exponential_buckets, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder,
+};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, ToSocketAddrs};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// 未设置模型名称时`model`标签的值
+const DEFAULT_MODEL: &str = "default";
+
+/// [`serve`]读取请求的超时时间
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+struct Metrics {
+    registry: Registry,
+    runs: IntCounterVec,
+    failures: IntCounterVec,
+    duration: HistogramVec,
+    batch_size: HistogramVec,
+    input_bytes: HistogramVec,
+    copy_duration: HistogramVec,
+}
+
+static METRICS: Lazy<Metrics> = Lazy::new(|| {
+    let registry = Registry::new();
+    let counter = |name: &str, help: &str| {
+        let counter = IntCounterVec::new(Opts::new(name, help), &["model"]).unwrap();
+        registry.register(Box::new(counter.clone())).unwrap();
+        counter
+    };
+    let histogram = |name: &str, help: &str, buckets: Vec<f64>, labels: &[&str]| {
+        let opts = HistogramOpts::new(name, help).buckets(buckets);
+        let histogram = HistogramVec::new(opts, labels).unwrap();
+        registry.register(Box::new(histogram.clone())).unwrap();
+        histogram
+    };
+
+    let seconds = exponential_buckets(0.0005, 2.0, 16).unwrap();
+    Metrics {
+        runs: counter("paddle_inference_runs_total", "预测次数"),
+        failures: counter("paddle_inference_run_failures_total", "预测失败的次数"),
+        duration: histogram(
+            "paddle_inference_run_duration_seconds",
+            "单次预测的耗时",
+            seconds.clone(),
+            &["model"],
+        ),
+        batch_size: histogram(
+            "paddle_inference_batch_size",
+            "第一个输入第一维的大小",
+            exponential_buckets(1.0, 2.0, 10).unwrap(),
+            &["model"],
+        ),
+        input_bytes: histogram(
+            "paddle_inference_input_bytes",
+            "所有输入占用的字节数",
+            exponential_buckets(1024.0, 4.0, 12).unwrap(),
+            &["model"],
+        ),
+        copy_duration: histogram(
+            "paddle_inference_copy_duration_seconds",
+            "复制输入/输出的耗时",
+            seconds,
+            &["model", "direction"],
+        ),
+        registry,
+    }
+});
+
+/// 注册了所有指标的[`Registry`]
+pub fn registry() -> &'static Registry {
+    &METRICS.registry
+}
+
+/// 以 Prometheus 文本格式输出所有指标
+pub fn gather() -> String {
+    TextEncoder::new()
+        .encode_to_string(&METRICS.registry.gather())
+        .unwrap_or_default()
+}
+
+/// 在`addr`启动 HTTP 接口，任意`GET`请求均返回[`gather`]的结果
+///
+/// 接口在后台线程中逐个处理请求，适用于没有其它 HTTP 服务的程序。读取请求超过 2 秒时不再等待，
+/// 避免空闲连接阻塞其它请求。监听失败时返回[`Error::Io`]
+pub fn serve<A: ToSocketAddrs>(addr: A) -> Result<(std::net::SocketAddr, JoinHandle<()>)> {
+    let listener = TcpListener::bind(addr).map_err(|e| Error::Io(e.to_string()))?;
+    let addr = listener
+        .local_addr()
+        .map_err(|e| Error::Io(e.to_string()))?;
+    let handle = std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if stream.set_read_timeout(Some(READ_TIMEOUT)).is_err() {
+                continue;
+            }
+            let mut reader = BufReader::new(&stream);
+            let mut line = String::new();
+            // 读取并忽略请求头，超时后仍返回指标
+            while reader.read_line(&mut line).map(|n| n > 2).unwrap_or(false) {
+                line.clear();
+            }
+            let body = gather();
+            let _ = write!(
+                &stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                prometheus::TEXT_FORMAT,
+                body.len(),
+                body
+            );
+        }
+    });
+    Ok((addr, handle))
+}
+
+pub(crate) fn observe_run(
+    model: Option<&str>,
+    duration: Duration,
+    ok: bool,
+    batch_size: u64,
+    input_bytes: u64,
+) {
+    let model = [model.unwrap_or(DEFAULT_MODEL)];
+    METRICS.runs.with_label_values(&model).inc();
+    if !ok {
+        METRICS.failures.with_label_values(&model).inc();
+    }
+    METRICS
+        .duration
+        .with_label_values(&model)
+        .observe(duration.as_secs_f64());
+    METRICS
+        .batch_size
+        .with_label_values(&model)
+        .observe(batch_size as f64);
+    METRICS
+        .input_bytes
+        .with_label_values(&model)
+        .observe(input_bytes as f64);
+}
+
+pub(crate) fn observe_copy(model: Option<&str>, direction: &str, duration: Duration) {
+    METRICS
+        .copy_duration
+        .with_label_values(&[model.unwrap_or(DEFAULT_MODEL), direction])
+        .observe(duration.as_secs_f64());
+}
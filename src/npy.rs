@@ -0,0 +1,349 @@
+//! NumPy `.npy`及`.npz`文件的读写
+//!
+//! 支持的数据类型为`float32`、`int64`、`int32`、`uint8`、`int8`，与[`DataType`]一一对应。
+//! 读取时支持任意字节序，写入时使用小端序；不支持 Fortran 顺序(列优先)的数组。
+//!
+//! `.npz`文件的读写需要启用`npz`特性，文件中每个数组的名称为去掉`.npy`后缀的文件名
+//!
+//! ``` no_run
+//! use paddle_inference::config::model::Model;
+//! use paddle_inference::{npy, Predictor};
+//!
+//! let mut predictor = Predictor::builder(Model::path("model", "params")).build()?;
+//! // Python 中使用 np.save("x.npy", x) 保存的输入
+//! predictor.input("x")?.copy_from_npy("x.npy")?;
+//! predictor.run()?;
+//! predictor.output("y")?.save_npy("y.npy")?;
+//!
+//! let expected = npy::load("expected_y.npy")?;
+//! assert_eq!(predictor.output_to_host("y")?, expected);
+//! # Ok::<_, paddle_inference::Error>(())
+//! ```
+
+use crate::common::DataType;
+#[cfg(feature = "npz")]
+use crate::host::NamedTensors;
+use crate::host::{element_count, HostData, HostTensor};
+use crate::tensor::Tensor;
+use crate::{Error, Result};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+/// 文件头长度上限，与 numpy 读取时的默认限制相同
+const MAX_HEADER_LEN: usize = 10000;
+
+/// 从`reader`中读取`.npy`格式的数组
+pub fn read<R: Read>(mut reader: R) -> Result<HostTensor> {
+    let mut prefix = [0; 8];
+    reader.read_exact(&mut prefix).map_err(io)?;
+    if &prefix[..6] != MAGIC {
+        return Err(invalid("缺少 npy 文件头"));
+    }
+    let header_len = match prefix[6] {
+        1 => {
+            let mut len = [0; 2];
+            reader.read_exact(&mut len).map_err(io)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0; 4];
+            reader.read_exact(&mut len).map_err(io)?;
+            u32::from_le_bytes(len) as usize
+        }
+        v => return Err(invalid(format!("不支持的版本{}", v))),
+    };
+    if header_len > MAX_HEADER_LEN {
+        return Err(invalid(format!(
+            "文件头长度{}超过上限{}",
+            header_len, MAX_HEADER_LEN
+        )));
+    }
+    let mut header = vec![0; header_len];
+    reader.read_exact(&mut header).map_err(io)?;
+    let header = std::str::from_utf8(&header).map_err(|_| invalid("文件头不是有效的文本"))?;
+    let header = Header::parse(header)?;
+
+    let mut data = vec![];
+    reader.read_to_end(&mut data).map_err(io)?;
+    header.decode(&data)
+}
+
+/// 将`tensor`以`.npy`格式写入`writer`
+pub fn write<W: Write>(mut writer: W, tensor: &HostTensor) -> Result<()> {
+    let (descr, data) = match tensor.data() {
+        HostData::Float32(v) => ("<f4", v.iter().flat_map(|x| x.to_le_bytes()).collect()),
+        HostData::Int64(v) => ("<i8", v.iter().flat_map(|x| x.to_le_bytes()).collect()),
+        HostData::Int32(v) => ("<i4", v.iter().flat_map(|x| x.to_le_bytes()).collect()),
+        HostData::Uint8(v) => ("|u1", v.clone()),
+        HostData::Int8(v) => ("|i1", v.iter().map(|&x| x as u8).collect::<Vec<_>>()),
+    };
+    let shape = match tensor.shape() {
+        [n] => format!("({},)", n),
+        shape => {
+            let dims = shape.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+            format!("({})", dims.join(", "))
+        }
+    };
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+        descr, shape
+    );
+
+    // 文件头以换行结尾，数据按 64 字节对齐；超过 u16 时使用 2.0 版本
+    let version = if header.len() + 64 < u16::MAX as usize {
+        1
+    } else {
+        2
+    };
+    let prefix_len = MAGIC.len() + 2 + if version == 1 { 2 } else { 4 };
+    let padding = 63 - (prefix_len + header.len()) % 64;
+    header.extend(std::iter::repeat_n(' ', padding));
+    header.push('\n');
+
+    let mut out = MAGIC.to_vec();
+    out.extend([version, 0]);
+    if version == 1 {
+        out.extend((header.len() as u16).to_le_bytes());
+    } else {
+        out.extend((header.len() as u32).to_le_bytes());
+    }
+    out.extend(header.as_bytes());
+    writer.write_all(&out).map_err(io)?;
+    writer.write_all(&data).map_err(io)?;
+    writer.flush().map_err(io)
+}
+
+/// 读取`.npy`文件
+pub fn load<P: AsRef<Path>>(path: P) -> Result<HostTensor> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| io_path(path, e))?;
+    read(BufReader::new(file))
+}
+
+/// 将`tensor`保存为`.npy`文件
+pub fn save<P: AsRef<Path>>(path: P, tensor: &HostTensor) -> Result<()> {
+    let path = path.as_ref();
+    let file = File::create(path).map_err(|e| io_path(path, e))?;
+    write(BufWriter::new(file), tensor)
+}
+
+/// 读取`.npz`文件中的所有数组，按在文件中的顺序返回
+///
+/// 支持`np.savez`及`np.savez_compressed`保存的文件
+#[cfg(feature = "npz")]
+pub fn load_npz<P: AsRef<Path>>(path: P) -> Result<NamedTensors> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| io_path(path, e))?;
+    let mut archive = zip::ZipArchive::new(BufReader::new(file)).map_err(zip_error)?;
+    let mut tensors = vec![];
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i).map_err(zip_error)?;
+        let name = entry.name();
+        let name = name.strip_suffix(".npy").unwrap_or(name).to_string();
+        tensors.push((name, read(entry)?));
+    }
+    Ok(tensors)
+}
+
+/// 将`tensors`保存为不压缩的`.npz`文件，与`np.savez`相同
+#[cfg(feature = "npz")]
+pub fn save_npz<P: AsRef<Path>>(path: P, tensors: &[(String, HostTensor)]) -> Result<()> {
+    let path = path.as_ref();
+    let file = File::create(path).map_err(|e| io_path(path, e))?;
+    let mut archive = zip::ZipWriter::new(BufWriter::new(file));
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for (name, tensor) in tensors {
+        archive
+            .start_file(format!("{}.npy", name), options)
+            .map_err(zip_error)?;
+        write(&mut archive, tensor)?;
+    }
+    archive.finish().map_err(zip_error)?;
+    Ok(())
+}
+
+impl Tensor<'_> {
+    /// 读取`.npy`文件，按文件中的 shape 调用[`Self::reshape`]并将数据复制到 Tensor 中
+    pub fn copy_from_npy<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        self.copy_from_host(&load(path)?)
+    }
+
+    /// 将 Tensor 的 shape 及数据保存为`.npy`文件，数据类型与[`Self::data_type`]相同
+    pub fn save_npy<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        save(path, &self.to_host()?)
+    }
+}
+
+/// `.npy`文件头中的数组信息
+#[derive(Debug, PartialEq)]
+struct Header {
+    data_type: DataType,
+    big_endian: bool,
+    shape: Vec<usize>,
+}
+
+impl Header {
+    /// 解析文件头中的 Python 字典，如`{'descr': '<f4', 'fortran_order': False, 'shape': (2, 3), }`
+    fn parse(header: &str) -> Result<Self> {
+        let descr = value(header, "descr")?.trim_matches(['\'', '"']);
+        let (order, kind) = match descr.as_bytes() {
+            [order @ (b'<' | b'>' | b'|' | b'='), kind @ ..] => (*order, kind),
+            kind => (b'=', kind),
+        };
+        let data_type = match kind {
+            b"f4" => DataType::Float32,
+            b"i8" => DataType::Int64,
+            b"i4" => DataType::Int32,
+            b"u1" => DataType::Uint8,
+            b"i1" => DataType::Int8,
+            _ => return Err(invalid(format!("不支持的数据类型`{}`", descr))),
+        };
+        let big_endian = order == b'>' || (order == b'=' && cfg!(target_endian = "big"));
+
+        if value(header, "fortran_order")? != "False" {
+            return Err(invalid("不支持 Fortran 顺序的数组"));
+        }
+        let shape = value(header, "shape")?
+            .trim_matches(['(', ')'])
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                s.trim_end_matches('L')
+                    .parse()
+                    .map_err(|_| invalid(format!("无效的 shape `{}`", s)))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            data_type,
+            big_endian,
+            shape,
+        })
+    }
+
+    fn decode(self, bytes: &[u8]) -> Result<HostTensor> {
+        macro_rules! decode {
+            ($ty: ty) => {{
+                let size = std::mem::size_of::<$ty>();
+                let len = element_count(&self.shape).and_then(|len| len.checked_mul(size));
+                if len != Some(bytes.len()) {
+                    return Err(invalid(format!(
+                        "数据长度{}与 shape {:?}不匹配",
+                        bytes.len(),
+                        self.shape
+                    )));
+                }
+                bytes
+                    .chunks_exact(size)
+                    .map(|b| {
+                        let b = b.try_into().unwrap();
+                        if self.big_endian {
+                            <$ty>::from_be_bytes(b)
+                        } else {
+                            <$ty>::from_le_bytes(b)
+                        }
+                    })
+                    .collect()
+            }};
+        }
+
+        let data = match self.data_type {
+            DataType::Float32 => HostData::Float32(decode!(f32)),
+            DataType::Int64 => HostData::Int64(decode!(i64)),
+            DataType::Int32 => HostData::Int32(decode!(i32)),
+            DataType::Uint8 => HostData::Uint8(decode!(u8)),
+            DataType::Int8 => HostData::Int8(decode!(i8)),
+            DataType::Unknown => unreachable!("Header::parse 不会返回 Unknown"),
+        };
+        HostTensor::from_data(self.shape, data)
+    }
+}
+
+/// 读取文件头字典中`key`对应的值
+fn value<'a>(header: &'a str, key: &str) -> Result<&'a str> {
+    let start = ["'", "\""]
+        .iter()
+        .find_map(|q| header.find(&format!("{q}{key}{q}")))
+        .ok_or_else(|| invalid(format!("文件头缺少`{}`", key)))?;
+    let rest = header[start + key.len() + 2..]
+        .trim_start()
+        .strip_prefix(':')
+        .ok_or_else(|| invalid(format!("文件头中`{}`的格式错误", key)))?
+        .trim_start();
+    let end = if rest.starts_with('(') {
+        rest.find(')').map(|i| i + 1)
+    } else {
+        rest.find([',', '}'])
+    }
+    .ok_or_else(|| invalid(format!("文件头中`{}`的格式错误", key)))?;
+    Ok(rest[..end].trim())
+}
+
+fn invalid<S: ToString>(reason: S) -> Error {
+    Error::InvalidNpy(reason.to_string())
+}
+
+fn io(e: std::io::Error) -> Error {
+    Error::Io(e.to_string())
+}
+
+fn io_path(path: &Path, e: std::io::Error) -> Error {
+    Error::Io(format!("`{}`: {}", path.display(), e))
+}
+
+#[cfg(feature = "npz")]
+fn zip_error(e: zip::result::ZipError) -> Error {
+    match e {
+        zip::result::ZipError::Io(e) => io(e),
+        e => invalid(e),
+    }
+}
+
+#[test]
+fn test_npy() {
+    for tensor in [
+        HostTensor::new(vec![2, 3], vec![1.5f32, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap(),
+        HostTensor::new(vec![3], vec![-1i8, 0, 1]).unwrap(),
+        HostTensor::new(vec![2, 0], Vec::<u8>::new()).unwrap(),
+        HostTensor::new(vec![], vec![i64::MIN]).unwrap(),
+    ] {
+        let mut bytes = vec![];
+        write(&mut bytes, &tensor).unwrap();
+        let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        assert!((10 + header_len).is_multiple_of(64));
+        assert_eq!(read(&bytes[..]).unwrap(), tensor);
+    }
+
+    // numpy 在大端序机器上或指定`>i4`时保存的文件
+    let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+    let header = "{'descr': '>i4', 'fortran_order': False, 'shape': (2,), }\n";
+    bytes.extend((header.len() as u16).to_le_bytes());
+    bytes.extend(header.as_bytes());
+    bytes.extend([0, 0, 0, 1, 0xff, 0xff, 0xff, 0xfe]);
+    assert_eq!(
+        read(&bytes[..]).unwrap(),
+        HostTensor::new(vec![2], vec![1i32, -2]).unwrap()
+    );
+
+    assert!(matches!(read(&b"not npy!"[..]), Err(Error::InvalidNpy(_))));
+    assert!(matches!(read(&bytes[..20]), Err(Error::Io(_))));
+    // 文件头长度超过上限时不分配内存
+    let mut large = b"\x93NUMPY\x02\x00".to_vec();
+    large.extend(u32::MAX.to_le_bytes());
+    assert!(matches!(read(&large[..]), Err(Error::InvalidNpy(_))));
+
+    // shape 之积溢出
+    let overflow = Header::parse(&header.replace("(2,)", "(4611686018427387904, 8)")).unwrap();
+    assert!(matches!(overflow.decode(&[]), Err(Error::InvalidNpy(_))));
+    // 数据字节数溢出
+    let overflow = Header::parse(&header.replace("(2,)", "(4611686018427387904,)")).unwrap();
+    assert!(matches!(overflow.decode(&[]), Err(Error::InvalidNpy(_))));
+
+    let fortran = header.replace("False", "True");
+    assert!(Header::parse(&fortran).is_err());
+    assert!(Header::parse(&header.replace(">i4", "<f8")).is_err());
+}
@@ -0,0 +1,157 @@
+//! 共享模型权重的预测器池
+
+use crate::config::Config;
+use crate::predictor::Predictor;
+use crate::Result;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// 预测器池
+///
+/// 通过[`Config::build`]创建一个预测器，再通过`PD_PredictorClone`克隆出其余预测器，所有预测器共享模型权重。
+/// 多个线程可以共享同一个池(池本身可以廉价克隆)，通过[`Self::acquire`]等方法获取空闲的预测器，
+/// 返回的[`PredictorGuard`]释放时自动归还预测器。
+///
+/// ``` no_run
+/// use paddle_inference::config::model::Model;
+/// use paddle_inference::{Predictor, PredictorPool};
+///
+/// let pool = PredictorPool::new(Predictor::builder(Model::path("model", "params")), 4)?;
+/// let handles = (0..8)
+///     .map(|_| {
+///         let pool = pool.clone();
+///         std::thread::spawn(move || {
+///             let mut predictor = pool.acquire();
+///             predictor.run()
+///         })
+///     })
+///     .collect::<Vec<_>>();
+/// # Ok::<_, paddle_inference::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct PredictorPool {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    idle: Mutex<Vec<Predictor>>,
+    available: Condvar,
+    size: usize,
+}
+
+impl Inner {
+    fn lock(&self) -> MutexGuard<'_, Vec<Predictor>> {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl PredictorPool {
+    /// 使用`config`创建包含`size`个预测器的池，`size`为`0`时按`1`处理
+    ///
+    /// 创建或克隆预测器失败时返回错误
+    pub fn new(config: Config, size: usize) -> Result<Self> {
+        let size = size.max(1);
+        let predictor = config.build()?;
+        let mut idle = Vec::with_capacity(size);
+        for _ in 1..size {
+            idle.push(predictor.try_clone()?);
+        }
+        idle.push(predictor);
+        Ok(Self {
+            inner: Arc::new(Inner {
+                idle: Mutex::new(idle),
+                available: Condvar::new(),
+                size,
+            }),
+        })
+    }
+
+    /// 池中预测器的总数
+    pub fn size(&self) -> usize {
+        self.inner.size
+    }
+
+    /// 当前空闲的预测器数量
+    pub fn idle(&self) -> usize {
+        self.inner.lock().len()
+    }
+
+    /// 获取空闲的预测器，没有空闲的预测器时阻塞直到其他线程归还
+    pub fn acquire(&self) -> PredictorGuard {
+        let mut idle = self.inner.lock();
+        loop {
+            if let Some(predictor) = idle.pop() {
+                return self.guard(predictor);
+            }
+            idle = self
+                .inner
+                .available
+                .wait(idle)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// 获取空闲的预测器，没有空闲的预测器时立即返回`None`
+    pub fn try_acquire(&self) -> Option<PredictorGuard> {
+        let predictor = self.inner.lock().pop()?;
+        Some(self.guard(predictor))
+    }
+
+    /// 获取空闲的预测器，超过`timeout`仍没有空闲的预测器时返回`None`
+    pub fn acquire_timeout(&self, timeout: Duration) -> Option<PredictorGuard> {
+        let deadline = Instant::now() + timeout;
+        let mut idle = self.inner.lock();
+        loop {
+            if let Some(predictor) = idle.pop() {
+                return Some(self.guard(predictor));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+            idle = self
+                .inner
+                .available
+                .wait_timeout(idle, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    fn guard(&self, predictor: Predictor) -> PredictorGuard {
+        PredictorGuard {
+            pool: self.inner.clone(),
+            predictor: Some(predictor),
+        }
+    }
+}
+
+/// 从[`PredictorPool`]中获取的预测器，释放时归还到池中
+pub struct PredictorGuard {
+    pool: Arc<Inner>,
+    predictor: Option<Predictor>,
+}
+
+impl Deref for PredictorGuard {
+    type Target = Predictor;
+
+    fn deref(&self) -> &Predictor {
+        self.predictor.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PredictorGuard {
+    fn deref_mut(&mut self) -> &mut Predictor {
+        self.predictor.as_mut().unwrap()
+    }
+}
+
+impl Drop for PredictorGuard {
+    fn drop(&mut self) {
+        if let Some(predictor) = self.predictor.take() {
+            self.pool.lock().push(predictor);
+            self.pool.available.notify_one();
+        }
+    }
+}
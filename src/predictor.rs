@@ -1,90 +1,404 @@
 use crate::call;
-use crate::common::OneDimArrayCstr;
+use crate::common::{DataType, OneDimArrayCstr};
 use crate::config::model::Model;
+use crate::config::report::ConfigReport;
 use crate::config::Config;
 use crate::ctypes::{
-    PD_Predictor, PD_PredictorClone, PD_PredictorDestroy, PD_PredictorGetInputHandle,
-    PD_PredictorGetInputNames, PD_PredictorGetInputNum, PD_PredictorGetOutputHandle,
-    PD_PredictorGetOutputNames, PD_PredictorGetOutputNum, PD_PredictorRun,
+    PD_Predictor, PD_PredictorClearIntermediateTensor, PD_PredictorClone, PD_PredictorDestroy,
+    PD_PredictorGetInputHandle, PD_PredictorGetInputNames, PD_PredictorGetInputNum,
+    PD_PredictorGetOutputHandle, PD_PredictorGetOutputNames, PD_PredictorGetOutputNum,
+    PD_PredictorRun, PD_PredictorTryShrinkMemory,
 };
-use crate::tensor::Tensor;
+use crate::host::{HostTensor, NamedTensors};
+use crate::runtime::Runtime;
+use crate::tensor::{Element, Tensor};
+use crate::try_call;
 use crate::utils::to_c_str;
+use crate::{Error, Result};
+use once_cell::sync::OnceCell;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 自动释放内存池的策略，默认不自动释放
+///
+/// paddle 在预测后会保留内存池中的内存供下次使用，偶尔出现的超大输入会使内存占用一直保持在峰值。
+/// 通过[`Predictor::set_shrink_policy`]设置后，满足条件时自动调用[`Predictor::try_shrink_memory`]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShrinkPolicy {
+    /// 所有输入占用的字节数超过该值时，在预测完成后释放内存
+    pub input_bytes: Option<u64>,
+    /// 最后一次预测后空闲超过该时间时释放内存，需要定期调用[`Predictor::shrink_if_idle`]，
+    /// [`crate::AsyncPredictor`]的工作线程会自动调用
+    pub idle: Option<Duration>,
+}
 
 /// Paddle Inference 的预测器
+///
+/// 预测器可以在线程间转移(`Send`)，但不能被多个线程同时使用(`!Sync`)。多线程共享同一模型时请使用
+/// [`Clone`]或[`crate::PredictorPool`]
 pub struct Predictor {
+    runtime: Runtime,
     ptr: *mut PD_Predictor,
+    report: Arc<ConfigReport>,
+    shrink: ShrinkPolicy,
+    /// 最后一次预测的时间，释放内存后为`None`
+    last_run: Option<Instant>,
+    model_name: Option<Arc<str>>,
+    /// 首次使用时缓存的输入名称，与克隆的预测器共享
+    cached_input_names: Arc<OnceCell<Vec<String>>>,
 }
 
+// SAFETY: `PD_Predictor`不依赖创建它的线程，同一时刻只会被持有`&mut Predictor`或`&Predictor`的线程使用
+unsafe impl Send for Predictor {}
+
 impl Predictor {
     pub fn builder(model: Model) -> Config {
         Config::new(model)
     }
 
-    pub(crate) fn from_ptr(ptr: *mut PD_Predictor) -> Self {
-        Self { ptr }
+    pub(crate) fn from_ptr(
+        runtime: Runtime,
+        ptr: *mut PD_Predictor,
+        report: ConfigReport,
+        model_name: Option<String>,
+    ) -> Self {
+        Self {
+            runtime,
+            ptr,
+            report: Arc::new(report),
+            shrink: ShrinkPolicy::default(),
+            last_run: None,
+            model_name: model_name.map(Arc::from),
+            cached_input_names: Arc::default(),
+        }
+    }
+
+    /// 创建预测器时使用的动态库
+    pub fn runtime(&self) -> &Runtime {
+        &self.runtime
+    }
+
+    /// 创建预测器时设置的[`Config::model_name`]
+    pub fn model_name(&self) -> Option<&str> {
+        self.model_name.as_deref()
+    }
+
+    /// 创建预测器时实际生效的配置
+    pub fn config_report(&self) -> &ConfigReport {
+        &self.report
     }
 }
 
 impl Predictor {
     /// 获取输入 Tensor 名称
     pub fn input_names(&self) -> OneDimArrayCstr {
-        let ptr = call! { PD_PredictorGetInputNames(self.ptr) };
-        OneDimArrayCstr::from_ptr(ptr)
+        let ptr = call! { &self.runtime => PD_PredictorGetInputNames(self.ptr) };
+        OneDimArrayCstr::from_ptr(&self.runtime, ptr)
+    }
+
+    /// 同[`Self::input_names`]，结果在首次调用时缓存
+    fn cached_input_names(&self) -> &[String] {
+        self.cached_input_names
+            .get_or_init(|| self.input_names().into())
     }
 
     /// 获取输入 Tensor 数量
     pub fn input_num(&self) -> usize {
-        call! { PD_PredictorGetInputNum(self.ptr) }
+        call! { &self.runtime => PD_PredictorGetInputNum(self.ptr) }
     }
 
     /// 根据名称获取输入 Tensor
     ///
+    /// 同名的 Tensor 共享数据，因此获取 Tensor 需要可变借用，同一时刻只能持有一个 Tensor：
+    ///
+    /// ```compile_fail
+    /// # fn f(mut predictor: paddle_inference::Predictor) -> paddle_inference::Result<()> {
+    /// let mut x = predictor.input("x")?;
+    /// let mut alias = predictor.input("x")?;
+    /// let data = x.as_mut_slice::<f32>(paddle_inference::common::PlaceType::Cpu).unwrap();
+    /// alias.reshape(&[1]);
+    /// data[0] = 1.0;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
     /// **注意:** 如果输入名称中包含字符`\0`，则只会将`\0`之前的字符作为输入
-    pub fn input(&self, name: &str) -> Tensor {
+    pub fn input(&mut self, name: &str) -> Result<Tensor<'_>> {
+        self.input_handle(name)
+    }
+
+    /// 同[`Self::input`]，仅供内部在不返回 Tensor 的方法中使用，调用者需保证同一时刻只使用一个同名的 Tensor
+    fn input_handle(&self, name: &str) -> Result<Tensor<'_>> {
         let (_n, name) = to_c_str(name);
-        let ptr = call! { PD_PredictorGetInputHandle(self.ptr, name) };
-        Tensor::from_ptr(ptr)
+        let ptr = try_call! { &self.runtime => PD_PredictorGetInputHandle(self.ptr, name) };
+        if ptr.is_null() {
+            return Err(Error::NullHandle("PD_PredictorGetInputHandle"));
+        }
+        Ok(Tensor::from_ptr(&self.runtime, ptr, self.model_name()))
     }
 
     /// 获取输出 Tensor 名称
     pub fn output_names(&self) -> OneDimArrayCstr {
-        let ptr = call! { PD_PredictorGetOutputNames(self.ptr) };
-        OneDimArrayCstr::from_ptr(ptr)
+        let ptr = call! { &self.runtime => PD_PredictorGetOutputNames(self.ptr) };
+        OneDimArrayCstr::from_ptr(&self.runtime, ptr)
     }
 
     /// 获取输出 Tensor 数量
     pub fn output_num(&self) -> usize {
-        call! { PD_PredictorGetOutputNum(self.ptr) }
+        call! { &self.runtime => PD_PredictorGetOutputNum(self.ptr) }
     }
 
-    /// 根据名称获取输出 Tensor
+    /// 根据名称获取输出 Tensor，与[`Self::input`]相同，获取 Tensor 需要可变借用
     ///
     /// **注意:** 如果输入名称中包含字符`\0`，则只会将`\0`之前的字符作为输入
-    pub fn output(&self, name: &str) -> Tensor {
+    pub fn output(&mut self, name: &str) -> Result<Tensor<'_>> {
+        self.output_handle(name)
+    }
+
+    /// 同[`Self::output`]，仅供内部在不返回 Tensor 的方法中使用
+    fn output_handle(&self, name: &str) -> Result<Tensor<'_>> {
         let (_n, name) = to_c_str(name);
-        let ptr = call! { PD_PredictorGetOutputHandle(self.ptr, name) };
-        Tensor::from_ptr(ptr)
+        let ptr = try_call! { &self.runtime => PD_PredictorGetOutputHandle(self.ptr, name) };
+        if ptr.is_null() {
+            return Err(Error::NullHandle("PD_PredictorGetOutputHandle"));
+        }
+        Ok(Tensor::from_ptr(&self.runtime, ptr, self.model_name()))
+    }
+}
+
+impl Predictor {
+    /// 设置输入 Tensor 的 shape 及数据
+    ///
+    /// 如果出现以下情况则设置失败
+    /// - 模型中不存在名称为`name`的输入
+    /// - `data`的长度与`shape`之积不相等
+    /// - `T`与输入 Tensor 的[`Tensor::data_type`]不匹配
+    pub fn set_input<T: Element>(&mut self, name: &str, shape: &[i32], data: &[T]) -> Result<()> {
+        let names = self.cached_input_names();
+        let mut tensor = self.check_input(names, name, shape, data.len(), T::DATA_TYPE)?;
+        tensor.reshape(shape);
+        tensor.copy_from(data)
+    }
+
+    /// 设置多个输入 Tensor 的 shape 及数据，值为`(名称, 数据)`，各输入的数据类型可以不同
+    ///
+    /// 所有输入均检查通过后才会写入数据，检查规则见[`Self::set_input`]
+    pub fn set_inputs<'a, I>(&mut self, inputs: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (&'a str, &'a HostTensor)>,
+    {
+        let names = self.cached_input_names();
+        let mut checked = vec![];
+        for (name, host) in inputs {
+            let shape = host
+                .shape()
+                .iter()
+                .map(|&d| i32::try_from(d))
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|_| Error::ShapeMismatch {
+                    shape: host.shape().iter().map(|&d| d as i32).collect(),
+                    len: host.len(),
+                })?;
+            let tensor = self.check_input(names, name, &shape, host.len(), host.data_type())?;
+            checked.push((tensor, host));
+        }
+
+        for (mut tensor, host) in checked {
+            tensor.copy_from_host(host)?;
+        }
+        Ok(())
+    }
+
+    /// 检查输入的名称、shape 及数据类型，返回对应的输入 Tensor
+    fn check_input(
+        &self,
+        names: &[String],
+        name: &str,
+        shape: &[i32],
+        len: usize,
+        data_type: DataType,
+    ) -> Result<Tensor<'_>> {
+        if !names.iter().any(|n| n == name) {
+            return Err(Error::TensorNotFound(name.to_string()));
+        }
+
+        let size = shape.iter().try_fold(1usize, |s, &i| {
+            usize::try_from(i).ok().and_then(|i| s.checked_mul(i))
+        });
+        if size != Some(len) {
+            return Err(Error::ShapeMismatch {
+                shape: shape.to_vec(),
+                len,
+            });
+        }
+
+        let tensor = self.input_handle(name)?;
+        let actual = tensor.data_type();
+        if actual != DataType::Unknown && actual != data_type {
+            return Err(Error::DataTypeMismatch {
+                expected: data_type,
+                actual,
+            });
+        }
+        Ok(tensor)
+    }
+}
+
+impl Predictor {
+    /// 将名称为`name`的输出复制到[`HostTensor`]中，模型中不存在该输出时返回[`Error::TensorNotFound`]
+    pub fn output_to_host(&self, name: &str) -> Result<HostTensor> {
+        let names: Vec<String> = self.output_names().into();
+        if !names.iter().any(|n| n == name) {
+            return Err(Error::TensorNotFound(name.to_string()));
+        }
+        self.output_handle(name)?.to_host()
+    }
+
+    /// 按[`Self::output_names`]的顺序将所有输出复制到[`HostTensor`]中，值为`(名称, 数据)`
+    pub fn outputs_to_host(&self) -> Result<NamedTensors> {
+        let names: Vec<String> = self.output_names().into();
+        names
+            .into_iter()
+            .map(|name| {
+                let host = self.output_handle(&name)?.to_host()?;
+                Ok((name, host))
+            })
+            .collect()
     }
 }
 
 impl Predictor {
     /// 执行模型预测，**需要在设置输入Tensor数据后调用**
-    pub fn run(&self) -> bool {
-        call! { PD_PredictorRun(self.ptr) }
+    ///
+    /// 预测会覆盖输出数据，因此需要可变借用，调用前需释放所有通过[`Self::input`]/[`Self::output`]获取的 Tensor
+    pub fn run(&mut self) -> Result<()> {
+        trace_span!("Predictor::run", model_name = self.model_name());
+        let (_batch_size, input_bytes) =
+            if self.shrink.input_bytes.is_some() || cfg!(feature = "metrics") {
+                self.input_stats()
+            } else {
+                (0, 0)
+            };
+        #[cfg(feature = "metrics")]
+        let start = Instant::now();
+        let ok = try_call! { &self.runtime => PD_PredictorRun(self.ptr) };
+        #[cfg(feature = "metrics")]
+        crate::metrics::observe_run(
+            self.model_name(),
+            start.elapsed(),
+            ok,
+            _batch_size,
+            input_bytes,
+        );
+        if !ok {
+            return Err(Error::RunFailed);
+        }
+        self.last_run = Some(Instant::now());
+        if self.shrink.input_bytes.is_some_and(|max| input_bytes > max) {
+            self.try_shrink_memory();
+        }
+        Ok(())
+    }
+
+    /// 将`inputs`复制到同名的输入 Tensor 中并执行预测，按[`Self::output_names`]的顺序返回所有输出
+    ///
+    /// 输入的检查规则同[`Self::set_inputs`]
+    pub fn run_host(&mut self, inputs: &[(String, HostTensor)]) -> Result<NamedTensors> {
+        trace_span!(
+            "Predictor::run_host",
+            model_name = self.model_name(),
+            inputs = inputs.len()
+        );
+        self.set_inputs(inputs.iter().map(|(name, tensor)| (name.as_str(), tensor)))?;
+        self.run()?;
+        self.outputs_to_host()
+    }
+}
+
+impl Predictor {
+    /// 释放预测过程中产生的中间 Tensor
+    pub fn clear_intermediate_tensors(&mut self) {
+        call! { &self.runtime => PD_PredictorClearIntermediateTensor(self.ptr) };
+    }
+
+    /// 释放内存池中未被占用的内存，返回释放的字节数
+    ///
+    /// 返回值可能小于实际释放的内存，因为部分内存不由内存池管理
+    pub fn try_shrink_memory(&mut self) -> u64 {
+        self.last_run = None;
+        call! { &self.runtime => PD_PredictorTryShrinkMemory(self.ptr) }
+    }
+
+    /// 设置自动释放内存的策略，克隆的预测器使用相同的策略
+    pub fn set_shrink_policy(&mut self, policy: ShrinkPolicy) {
+        self.shrink = policy;
+    }
+
+    /// 自动释放内存的策略
+    pub fn shrink_policy(&self) -> &ShrinkPolicy {
+        &self.shrink
+    }
+
+    /// 最后一次预测后空闲时间超过[`ShrinkPolicy::idle`]且尚未释放内存时释放内存，返回
+    /// [`Self::try_shrink_memory`]的结果，未释放时返回`None`
+    pub fn shrink_if_idle(&mut self) -> Option<u64> {
+        let idle = self.shrink.idle?;
+        if self.last_run?.elapsed() < idle {
+            return None;
+        }
+        Some(self.try_shrink_memory())
+    }
+
+    /// 第一个输入第一维的大小及所有输入占用的字节数
+    fn input_stats(&self) -> (u64, u64) {
+        let mut batch_size = None;
+        let mut input_bytes = 0;
+        for name in self.cached_input_names() {
+            let Ok(tensor) = self.input_handle(name) else {
+                continue;
+            };
+            let shape = tensor.shape();
+            batch_size.get_or_insert_with(|| shape.first().map_or(0, |&d| d.max(0) as u64));
+            let len = shape.iter().map(|&d| d.max(0) as u64).product::<u64>();
+            input_bytes += len * tensor.data_type().size() as u64;
+        }
+        (batch_size.unwrap_or(0), input_bytes)
+    }
+}
+
+impl Predictor {
+    /// 通过`PD_PredictorClone`克隆预测器，克隆的预测器与原预测器共享模型权重
+    ///
+    /// 动态库返回空指针时返回[`Error::NullHandle`]
+    pub fn try_clone(&self) -> Result<Self> {
+        let ptr = try_call! { &self.runtime => PD_PredictorClone(self.ptr) };
+        if ptr.is_null() {
+            return Err(Error::NullHandle("PD_PredictorClone"));
+        }
+        Ok(Self {
+            runtime: self.runtime.clone(),
+            ptr,
+            report: self.report.clone(),
+            shrink: self.shrink.clone(),
+            last_run: None,
+            model_name: self.model_name.clone(),
+            cached_input_names: self.cached_input_names.clone(),
+        })
     }
 }
 
 impl Clone for Predictor {
+    /// 同[`Predictor::try_clone`]，克隆失败时 panic
     fn clone(&self) -> Self {
-        let ptr = call! { PD_PredictorClone(self.ptr) };
-        Self { ptr }
+        self.try_clone().expect("克隆预测器失败")
     }
 }
 
 impl Drop for Predictor {
     fn drop(&mut self) {
         call! {
-            PD_PredictorDestroy(self.ptr)
+            &self.runtime => PD_PredictorDestroy(self.ptr)
         };
     }
 }
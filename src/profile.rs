@@ -0,0 +1,380 @@
+//! 解析 paddle 的 profile 报告
+
+use crate::{Error, Result};
+use std::fmt::{Display, Formatter};
+
+/// 单种运算的耗时统计，时间单位为毫秒
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpProfile {
+    /// 运算类型，如`conv2d`
+    pub op_type: String,
+    /// 执行次数
+    pub calls: u64,
+    /// 总耗时
+    pub total: f64,
+    /// 平均耗时
+    pub avg: f64,
+    /// 单次最小耗时
+    pub min: f64,
+    /// 单次最大耗时
+    pub max: f64,
+    /// 总耗时占所有事件耗时的比例，范围为`0~1`
+    pub ratio: f64,
+}
+
+impl OpProfile {
+    /// 合并另一个线程中同类型运算的统计
+    fn merge(&mut self, other: &OpProfile) {
+        self.calls += other.calls;
+        self.total += other.total;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.avg = if self.calls > 0 {
+            self.total / self.calls as f64
+        } else {
+            0.0
+        };
+        self.ratio += other.ratio;
+    }
+}
+
+/// [`ProfileReport::sort_by`]的排序依据，均按降序排列
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProfileSortKey {
+    Calls,
+    Total,
+    Avg,
+    Min,
+    Max,
+}
+
+/// 两份报告中同一种运算的对比，见[`ProfileReport::diff`]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpDiff {
+    pub op_type: String,
+    /// 在原报告中的统计，不存在时为`None`
+    pub base: Option<OpProfile>,
+    /// 在对比报告中的统计，不存在时为`None`
+    pub other: Option<OpProfile>,
+}
+
+impl OpDiff {
+    /// 总耗时的变化量，不存在的一方按`0`计算
+    pub fn total_delta(&self) -> f64 {
+        let total = |p: &Option<OpProfile>| p.as_ref().map_or(0.0, |p| p.total);
+        total(&self.other) - total(&self.base)
+    }
+
+    /// 平均耗时的加速比(原耗时/对比耗时)，任意一方不存在或对比耗时为`0`时返回`None`
+    pub fn speedup(&self) -> Option<f64> {
+        let (base, other) = (self.base.as_ref()?, self.other.as_ref()?);
+        (other.avg > 0.0).then(|| base.avg / other.avg)
+    }
+}
+
+/// paddle profile 报告中按运算类型汇总的耗时统计
+///
+/// 启用[`crate::config::Config::profile`]后，paddle 在释放预测器时向标准输出打印 profile 报告，
+/// 其中`Event Summary`表格的每一行为一个事件。解析时去掉事件名称中的线程前缀(`thread0::`)，忽略名称中包含`/`的子事件，
+/// 并合并不同线程中同类型的运算。启用`serde`特性时可以序列化为 JSON 等格式。
+///
+/// ``` no_run
+/// use paddle_inference::config::model::Model;
+/// use paddle_inference::{Predictor, ProfileReport, ProfileSortKey};
+///
+/// let mut predictor = Predictor::builder(Model::path("model", "params"))
+///     .enable_profile()
+///     .build()?;
+/// predictor.run()?;
+/// let ((), mut report) = ProfileReport::capture(|| drop(predictor))?;
+/// report.sort_by(ProfileSortKey::Total);
+/// println!("{}", report);
+/// # Ok::<_, paddle_inference::Error>(())
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProfileReport {
+    /// 按报告中首次出现的顺序排列的运算
+    pub ops: Vec<OpProfile>,
+}
+
+impl ProfileReport {
+    /// 解析 paddle 打印的 profile 报告，`text`中包含多份报告时合并所有报告
+    ///
+    /// `text`中不存在`Event Summary`表格时返回[`Error::InvalidProfile`]
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut report = Self::default();
+        let mut found = false;
+        let mut in_table = false;
+        for line in text.lines().map(str::trim) {
+            if line.starts_with("Event") && line.contains("Calls") {
+                found = true;
+                in_table = true;
+                continue;
+            }
+            if !in_table || line.is_empty() {
+                continue;
+            }
+            match parse_row(line) {
+                Some(Some(op)) => match report.ops.iter_mut().find(|o| o.op_type == op.op_type) {
+                    Some(o) => o.merge(&op),
+                    None => report.ops.push(op),
+                },
+                Some(None) => {}
+                None => in_table = false,
+            }
+        }
+        if found {
+            Ok(report)
+        } else {
+            Err(Error::InvalidProfile(
+                "不存在`Event Summary`表格".to_string(),
+            ))
+        }
+    }
+
+    /// 调用`f`并捕获期间写入标准输出的内容，解析为 profile 报告
+    ///
+    /// paddle 在释放预测器时打印报告，因此通常在`f`中释放预测器。捕获期间其它线程写入标准输出的内容同样会被捕获。
+    /// 仅支持 unix 平台
+    #[cfg(unix)]
+    pub fn capture<R, F: FnOnce() -> R>(f: F) -> Result<(R, Self)> {
+        let (r, text) = capture_stdout(f).map_err(|e| Error::Io(e.to_string()))?;
+        // 将捕获的内容原样输出，避免隐藏其它日志
+        print!("{}", text);
+        Ok((r, Self::parse(&text)?))
+    }
+
+    /// 获取指定类型的运算
+    pub fn get(&self, op_type: &str) -> Option<&OpProfile> {
+        self.ops.iter().find(|op| op.op_type == op_type)
+    }
+
+    /// 所有运算的总耗时之和
+    pub fn total(&self) -> f64 {
+        self.ops.iter().map(|op| op.total).sum()
+    }
+
+    /// 按`key`降序排列
+    pub fn sort_by(&mut self, key: ProfileSortKey) {
+        let value = |op: &OpProfile| match key {
+            ProfileSortKey::Calls => op.calls as f64,
+            ProfileSortKey::Total => op.total,
+            ProfileSortKey::Avg => op.avg,
+            ProfileSortKey::Min => op.min,
+            ProfileSortKey::Max => op.max,
+        };
+        self.ops.sort_by(|a, b| value(b).total_cmp(&value(a)));
+    }
+
+    /// 与`other`(如使用另一种配置得到的报告)对比，结果按总耗时变化量的绝对值降序排列
+    pub fn diff(&self, other: &Self) -> Vec<OpDiff> {
+        let mut diff = self
+            .ops
+            .iter()
+            .map(|op| OpDiff {
+                op_type: op.op_type.clone(),
+                base: Some(op.clone()),
+                other: other.get(&op.op_type).cloned(),
+            })
+            .collect::<Vec<_>>();
+        diff.extend(
+            other
+                .ops
+                .iter()
+                .filter(|op| self.get(&op.op_type).is_none())
+                .map(|op| OpDiff {
+                    op_type: op.op_type.clone(),
+                    base: None,
+                    other: Some(op.clone()),
+                }),
+        );
+        diff.sort_by(|a, b| b.total_delta().abs().total_cmp(&a.total_delta().abs()));
+        diff
+    }
+}
+
+impl Display for ProfileReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:<32}{:>10}{:>14}{:>12}{:>12}{:>12}{:>10}",
+            "运算", "次数", "总耗时(ms)", "平均", "最小", "最大", "占比"
+        )?;
+        for op in &self.ops {
+            write!(
+                f,
+                "\n{:<32}{:>10}{:>14.3}{:>12.3}{:>12.3}{:>12.3}{:>9.2}%",
+                op.op_type,
+                op.calls,
+                op.total,
+                op.avg,
+                op.min,
+                op.max,
+                op.ratio * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 解析`Event Summary`中的一行，不是表格行时返回`None`，子事件返回`Some(None)`
+///
+/// 不同版本的表格在`Total`与`Min.`之间可能包含`CPU Time (Ratio)`等列，因此只使用前两列及最后四列
+fn parse_row(line: &str) -> Option<Option<OpProfile>> {
+    let mut tokens = line.split_whitespace();
+    let name = tokens.next()?;
+    let values = tokens
+        .filter(|t| !t.starts_with('('))
+        .map(|t| match t.strip_suffix('%') {
+            Some(t) => t.parse::<f64>().ok().map(|v| v / 100.0),
+            None => t.parse::<f64>().ok(),
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if values.len() < 6 {
+        return None;
+    }
+    let op_type = name.split_once("::").map_or(name, |(_, event)| event);
+    if op_type.contains('/') {
+        return Some(None);
+    }
+    let n = values.len();
+    Some(Some(OpProfile {
+        op_type: op_type.to_string(),
+        calls: values[0] as u64,
+        total: values[1],
+        min: values[n - 4],
+        max: values[n - 3],
+        avg: values[n - 2],
+        ratio: values[n - 1],
+    }))
+}
+
+/// 将标准输出(文件描述符`1`)重定向到临时文件，调用`f`后恢复并读取文件内容
+#[cfg(unix)]
+fn capture_stdout<R, F: FnOnce() -> R>(f: F) -> std::io::Result<(R, String)> {
+    use std::io::{Read, Seek, Write};
+    use std::os::unix::io::AsRawFd;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 释放时将标准输出恢复为保存的文件描述符并关闭它，`f` panic 时同样会恢复
+    struct Restore(libc::c_int);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let _ = std::io::stdout().flush();
+            unsafe {
+                libc::dup2(self.0, 1);
+                libc::close(self.0);
+            }
+        }
+    }
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let path = std::env::temp_dir().join(format!(
+        "paddle_inference_profile_{}_{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    let _ = std::fs::remove_file(&path);
+
+    std::io::stdout().flush()?;
+    let saved = unsafe { libc::dup(1) };
+    if saved < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let restore = Restore(saved);
+    if unsafe { libc::dup2(file.as_raw_fd(), 1) } < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let r = f();
+    drop(restore);
+
+    let mut text = String::new();
+    file.rewind()?;
+    file.read_to_string(&mut text)?;
+    Ok((r, text))
+}
+
+#[test]
+fn test_parse() {
+    let text = "
+------------------------->     Profiling Report     <-------------------------
+
+Place: All
+Time unit: ms
+
+-------------------------     Overhead Summary      -------------------------
+
+Total time: 12.5
+  Computation time       Total: 10     Ratio: 80%
+
+-------------------------       Event Summary       -------------------------
+
+Event                            Calls       Total       CPU Time (Ratio)        GPU Time (Ratio)        Min.        Max.        Ave.        Ratio.
+thread0::conv2d                  4           8           8 (1.000000)            0 (0.000000)            1.5         2.5         2           0.64
+thread0::conv2d/compute          4           7           7 (1.000000)            0 (0.000000)            1.5         2           1.75        0.56
+thread1::conv2d                  2           2           2 (1.000000)            0 (0.000000)            0.5         1.5         1           0.16
+thread0::relu                    4           0.5         0.5 (1.000000)          0 (0.000000)            0.1         0.2         0.125       4%
+I1017 12:00:00.000000 123 analysis_predictor.cc:1] done
+";
+    let mut report = ProfileReport::parse(text).unwrap();
+    assert_eq!(report.ops.len(), 2);
+    let conv = report.get("conv2d").unwrap();
+    assert_eq!(conv.calls, 6);
+    assert_eq!((conv.total, conv.min, conv.max), (10.0, 0.5, 2.5));
+    assert!((conv.avg - 10.0 / 6.0).abs() < 1e-9);
+    assert!((conv.ratio - 0.8).abs() < 1e-9);
+    assert!((report.get("relu").unwrap().ratio - 0.04).abs() < 1e-9);
+
+    report.sort_by(ProfileSortKey::Calls);
+    assert_eq!(report.ops[0].op_type, "conv2d");
+    report.sort_by(ProfileSortKey::Min);
+    assert_eq!(report.ops[0].op_type, "conv2d");
+    assert_eq!(report.to_string().lines().count(), 3);
+
+    let mut other = report.clone();
+    other.ops.retain(|op| op.op_type == "conv2d");
+    other.ops[0].total = 5.0;
+    other.ops[0].avg = 1.0;
+    let diff = report.diff(&other);
+    assert_eq!(diff[0].op_type, "conv2d");
+    assert_eq!(diff[0].total_delta(), -5.0);
+    assert!((diff[0].speedup().unwrap() - 10.0 / 6.0).abs() < 1e-9);
+    assert_eq!(diff[1].other, None);
+    assert_eq!(diff[1].speedup(), None);
+
+    assert!(matches!(
+        ProfileReport::parse("no report"),
+        Err(Error::InvalidProfile(_))
+    ));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_capture_stdout() {
+    fn stdout_id() -> (u64, u64) {
+        let mut stat = unsafe { std::mem::zeroed::<libc::stat>() };
+        assert_eq!(unsafe { libc::fstat(1, &mut stat) }, 0);
+        (stat.st_dev as u64, stat.st_ino as u64)
+    }
+
+    let before = stdout_id();
+    // 测试框架会捕获`print!`，因此直接写入文件描述符`1`
+    let (n, text) =
+        capture_stdout(|| unsafe { libc::write(1, b"report".as_ptr().cast(), 6) }).unwrap();
+    assert_eq!((n, text.as_str()), (6, "report"));
+    assert_eq!(stdout_id(), before);
+
+    // `f` panic 时同样恢复标准输出
+    assert!(std::panic::catch_unwind(|| capture_stdout(|| panic!("profile"))).is_err());
+    assert_eq!(stdout_id(), before);
+}
@@ -0,0 +1,272 @@
+//! `paddle_inference_c`动态库的加载
+
+use crate::ctypes::{function_names, Function, PD_GetVersion};
+use crate::{Error, Result};
+use libloading::{library_filename, Library, Symbol};
+use once_cell::sync::OnceCell;
+use std::collections::BTreeMap;
+use std::ffi::{CStr, OsStr};
+use std::fmt::{Debug, Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+static DEFAULT: OnceCell<Runtime> = OnceCell::new();
+
+/// 已加载的`paddle_inference_c`动态库
+///
+/// 可以通过[`crate::config::Config::runtime`]指定创建[`crate::Predictor`]时使用的动态库，未指定时使用
+/// [`Runtime::default_runtime`]
+#[derive(Clone)]
+pub struct Runtime {
+    library: Arc<Library>,
+    path: PathBuf,
+    capabilities: Arc<Capabilities>,
+}
+
+impl Runtime {
+    /// 动态库名称
+    pub const LIBRARY_NAME: &'static str = "paddle_inference_c";
+
+    /// [`Runtime::default_runtime`]读取的环境变量名称
+    pub const ENV_NAME: &'static str = "PADDLE_INFERENCE_LIB";
+
+    /// 加载指定路径的动态库，并检查[`crate::ctypes`]中声明的所有函数是否存在
+    ///
+    /// 如果`path`只包含文件名，则按照系统的动态库搜索规则查找
+    pub fn load<P: AsRef<OsStr>>(path: P) -> Result<Self> {
+        let path = PathBuf::from(path.as_ref());
+        let library = unsafe { Library::new(&path) }.map_err(|e| Error::LibraryLoad {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+        let capabilities = Capabilities::probe(&library);
+        Ok(Self {
+            library: Arc::new(library),
+            path,
+            capabilities: Arc::new(capabilities),
+        })
+    }
+
+    /// 依次在`dirs`中查找并加载动态库，返回第一个加载成功的结果
+    pub fn search<I, P>(dirs: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut errors = vec![];
+        for dir in dirs {
+            let path = dir.as_ref().join(library_filename(Self::LIBRARY_NAME));
+            if !path.is_file() {
+                continue;
+            }
+            match Self::load(&path) {
+                Ok(runtime) => return Ok(runtime),
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+
+        Err(Error::LibraryLoad {
+            path: library_filename(Self::LIBRARY_NAME)
+                .to_string_lossy()
+                .to_string(),
+            reason: if errors.is_empty() {
+                "搜索路径中不存在动态库".to_string()
+            } else {
+                errors.join("; ")
+            },
+        })
+    }
+
+    /// 根据环境变量加载动态库
+    ///
+    /// 环境变量的值可以为动态库文件路径，也可以为使用系统路径分隔符分隔的目录列表，此时使用[`Self::search`]加载
+    pub fn from_env(name: &str) -> Result<Self> {
+        let value = std::env::var_os(name).ok_or_else(|| Error::LibraryLoad {
+            path: format!("${}", name),
+            reason: "环境变量不存在".to_string(),
+        })?;
+
+        if Path::new(&value).is_file() {
+            Self::load(value)
+        } else {
+            Self::search(std::env::split_paths(&value))
+        }
+    }
+
+    /// 获取默认动态库
+    ///
+    /// 如果没有通过[`Self::set_default`]设置，则在首次调用时加载：
+    /// 1. 如果设置了环境变量[`Self::ENV_NAME`]，则使用[`Self::from_env`]加载
+    /// 2. 否则按照系统的动态库搜索规则加载[`Self::LIBRARY_NAME`]
+    ///
+    /// 加载失败时不会缓存错误，下次调用时将重新加载
+    pub fn default_runtime() -> Result<&'static Self> {
+        DEFAULT.get_or_try_init(|| {
+            if std::env::var_os(Self::ENV_NAME).is_some() {
+                Self::from_env(Self::ENV_NAME)
+            } else {
+                Self::load(library_filename(Self::LIBRARY_NAME))
+            }
+        })
+    }
+
+    /// 设置默认动态库
+    ///
+    /// 默认动态库已经加载或设置时返回`Err(self)`
+    pub fn set_default(self) -> std::result::Result<(), Self> {
+        DEFAULT.set(self)
+    }
+
+    /// 动态库路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 动态库支持的函数及版本信息
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    /// 从动态库中获取函数
+    pub fn get<F: Function + ?Sized>(&self) -> Result<Symbol<'_, F::Type>> {
+        unsafe { self.library.get(F::NAME.as_bytes()) }.map_err(|_| Error::MissingSymbol(F::NAME))
+    }
+}
+
+impl Debug for Runtime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Runtime")
+            .field("path", &self.path)
+            .field("version", &self.capabilities.version)
+            .finish()
+    }
+}
+
+/// 动态库支持的函数及版本信息
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// `PD_GetVersion`返回的版本信息，动态库中不存在该函数时为`None`
+    pub version_info: Option<String>,
+    /// 从[`Self::version_info`]中解析出的版本号
+    pub version: Option<Version>,
+    /// [`crate::ctypes`]中声明的函数名称及其是否存在于动态库中
+    pub functions: BTreeMap<String, bool>,
+}
+
+impl Capabilities {
+    fn probe(library: &Library) -> Self {
+        let functions = function_names()
+            .map(|name| {
+                let exists =
+                    unsafe { library.get::<unsafe extern "C" fn()>(name.as_bytes()) }.is_ok();
+                (name.to_string(), exists)
+            })
+            .collect();
+
+        let version_info = unsafe {
+            library
+                .get::<<PD_GetVersion as Function>::Type>(PD_GetVersion::NAME.as_bytes())
+                .ok()
+                .map(|f| f())
+                .filter(|ptr| !ptr.is_null())
+                .map(|ptr| CStr::from_ptr(ptr).to_string_lossy().to_string())
+        };
+        let version = version_info.as_deref().and_then(Version::parse);
+
+        Self {
+            version_info,
+            version,
+            functions,
+        }
+    }
+
+    /// 动态库中是否存在指定的函数
+    pub fn has(&self, name: &str) -> bool {
+        self.functions.get(name).copied().unwrap_or(false)
+    }
+
+    /// 动态库中不存在的函数
+    pub fn missing(&self) -> impl Iterator<Item = &str> {
+        self.functions
+            .iter()
+            .filter(|(_, exists)| !**exists)
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+/// `paddle_inference_c`的版本号
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    /// 从版本信息中解析版本号
+    ///
+    /// 优先解析以`version:`开头的行，否则使用第一个形如`x.y`或`x.y.z`的字符串
+    pub fn parse(info: &str) -> Option<Self> {
+        info.lines()
+            .find_map(|l| l.trim().strip_prefix("version:"))
+            .and_then(|v| Self::parse_number(v.trim()))
+            .or_else(|| {
+                info.split(|c: char| !(c.is_ascii_digit() || c == '.'))
+                    .find_map(Self::parse_number)
+            })
+    }
+
+    fn parse_number(s: &str) -> Option<Self> {
+        let mut iter = s.split('.');
+        let major = iter.next()?.parse().ok()?;
+        let minor = iter.next()?.parse().ok()?;
+        let patch = iter.next().map(|p| p.parse().ok()).unwrap_or(Some(0))?;
+        Some(Self {
+            major,
+            minor,
+            patch,
+        })
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[test]
+fn test_load_error() {
+    assert!(matches!(
+        Runtime::load("paddle_inference_c_not_exist"),
+        Err(Error::LibraryLoad { .. })
+    ));
+    assert!(matches!(
+        Runtime::from_env("PADDLE_INFERENCE_LIB_NOT_EXIST"),
+        Err(Error::LibraryLoad { .. })
+    ));
+    assert!(matches!(
+        Runtime::search(["/paddle_inference_c_not_exist"]),
+        Err(Error::LibraryLoad { .. })
+    ));
+}
+
+#[test]
+fn test_parse_version() {
+    let info = "version: 2.4.2\ncommit: 0e92adceae\nbranch: HEAD";
+    assert_eq!(
+        Version::parse(info),
+        Some(Version {
+            major: 2,
+            minor: 4,
+            patch: 2
+        })
+    );
+    assert_eq!(
+        Version::parse("v2.3").map(|v| v.to_string()),
+        Some("2.3.0".to_string())
+    );
+    assert_eq!(Version::parse("unknown"), None);
+}
@@ -1,316 +1,420 @@
-use crate::call;
 use crate::common::{DataType, OneDimArrayInt32, PlaceType, TwoDimArraySize};
 use crate::ctypes::{
-    PD_Tensor, PD_TensorCopyFromCpuFloat, PD_TensorCopyFromCpuInt32, PD_TensorCopyFromCpuInt64,
-    PD_TensorCopyFromCpuInt8, PD_TensorCopyFromCpuUint8, PD_TensorCopyToCpuFloat,
-    PD_TensorCopyToCpuInt32, PD_TensorCopyToCpuInt64, PD_TensorCopyToCpuInt8,
-    PD_TensorCopyToCpuUint8, PD_TensorDataFloat, PD_TensorDataInt32, PD_TensorDataInt64,
-    PD_TensorDataInt8, PD_TensorDataUint8, PD_TensorDestroy, PD_TensorGetDataType, PD_TensorGetLod,
-    PD_TensorGetName, PD_TensorGetShape, PD_TensorMutableDataFloat, PD_TensorMutableDataInt32,
-    PD_TensorMutableDataInt64, PD_TensorMutableDataInt8, PD_TensorMutableDataUint8,
-    PD_TensorReshape, PD_TensorSetLod,
+    Function, PD_PlaceType, PD_Tensor, PD_TensorCopyFromCpuFloat, PD_TensorCopyFromCpuInt32,
+    PD_TensorCopyFromCpuInt64, PD_TensorCopyFromCpuInt8, PD_TensorCopyFromCpuUint8,
+    PD_TensorCopyToCpuFloat, PD_TensorCopyToCpuInt32, PD_TensorCopyToCpuInt64,
+    PD_TensorCopyToCpuInt8, PD_TensorCopyToCpuUint8, PD_TensorDataFloat, PD_TensorDataInt32,
+    PD_TensorDataInt64, PD_TensorDataInt8, PD_TensorDataUint8, PD_TensorDestroy,
+    PD_TensorGetDataType, PD_TensorGetLod, PD_TensorGetName, PD_TensorGetShape,
+    PD_TensorMutableDataFloat, PD_TensorMutableDataInt32, PD_TensorMutableDataInt64,
+    PD_TensorMutableDataInt8, PD_TensorMutableDataUint8, PD_TensorReshape, PD_TensorSetLod,
 };
+use crate::host::{HostData, HostTensor};
+use crate::lod::Lod;
+use crate::predictor::Predictor;
+use crate::runtime::Runtime;
+use crate::{call, try_call};
+use crate::{Error, Result};
 use std::borrow::Cow;
 use std::ffi::CStr;
+use std::marker::PhantomData;
+
+/// Tensor 支持的数据类型
+///
+/// 该特质已密封，仅为`f32`、`i64`、`i32`、`u8`、`i8`实现
+pub trait Element: private::Sealed + Copy + Default + Send + Sync + 'static {
+    /// 对应的[`DataType`]
+    const DATA_TYPE: DataType;
+
+    #[doc(hidden)]
+    type MutableData: Function<
+        Type = unsafe extern "C" fn(*mut PD_Tensor, PD_PlaceType) -> *mut Self,
+    >;
+    #[doc(hidden)]
+    type Data: Function<
+        Type = unsafe extern "C" fn(*mut PD_Tensor, *mut PD_PlaceType, *mut i32) -> *mut Self,
+    >;
+    #[doc(hidden)]
+    type CopyFromCpu: Function<Type = unsafe extern "C" fn(*mut PD_Tensor, *const Self)>;
+    #[doc(hidden)]
+    type CopyToCpu: Function<Type = unsafe extern "C" fn(*mut PD_Tensor, *mut Self)>;
+
+    #[doc(hidden)]
+    fn into_host_data(data: Vec<Self>) -> HostData;
+    #[doc(hidden)]
+    fn from_host_data(data: HostData) -> Option<Vec<Self>>;
+    #[doc(hidden)]
+    fn host_data_slice(data: &HostData) -> Option<&[Self]>;
+    #[doc(hidden)]
+    fn host_data_slice_mut(data: &mut HostData) -> Option<&mut [Self]>;
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! element {
+    ($( $ty: ty => $data_type: ident, $mutable_data: ident, $data: ident, $copy_from: ident, $copy_to: ident; )*) => {
+        $(
+            impl private::Sealed for $ty {}
+            impl Element for $ty {
+                const DATA_TYPE: DataType = DataType::$data_type;
+                type MutableData = $mutable_data;
+                type Data = $data;
+                type CopyFromCpu = $copy_from;
+                type CopyToCpu = $copy_to;
+
+                fn into_host_data(data: Vec<Self>) -> HostData {
+                    HostData::$data_type(data)
+                }
+
+                fn from_host_data(data: HostData) -> Option<Vec<Self>> {
+                    match data {
+                        HostData::$data_type(data) => Some(data),
+                        _ => None,
+                    }
+                }
+
+                fn host_data_slice(data: &HostData) -> Option<&[Self]> {
+                    match data {
+                        HostData::$data_type(data) => Some(data),
+                        _ => None,
+                    }
+                }
+
+                fn host_data_slice_mut(data: &mut HostData) -> Option<&mut [Self]> {
+                    match data {
+                        HostData::$data_type(data) => Some(data),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+element! {
+    f32 => Float32, PD_TensorMutableDataFloat, PD_TensorDataFloat, PD_TensorCopyFromCpuFloat, PD_TensorCopyToCpuFloat;
+    i64 => Int64, PD_TensorMutableDataInt64, PD_TensorDataInt64, PD_TensorCopyFromCpuInt64, PD_TensorCopyToCpuInt64;
+    i32 => Int32, PD_TensorMutableDataInt32, PD_TensorDataInt32, PD_TensorCopyFromCpuInt32, PD_TensorCopyToCpuInt32;
+    u8 => Uint8, PD_TensorMutableDataUint8, PD_TensorDataUint8, PD_TensorCopyFromCpuUint8, PD_TensorCopyToCpuUint8;
+    i8 => Int8, PD_TensorMutableDataInt8, PD_TensorDataInt8, PD_TensorCopyFromCpuInt8, PD_TensorCopyToCpuInt8;
+}
 
 /// Tensor 是 Paddle Inference 的数据组织形式，用于对底层数据进行封装并提供接口对数据进行操作，包括设置 Shape、
 /// 数据、LoD 信息等。
-pub struct Tensor {
+///
+/// Tensor 借用创建它的[`crate::Predictor`]，因此在 Tensor 及其数据视图释放前无法调用
+/// [`crate::Predictor::run`]或释放预测器：
+///
+/// ```compile_fail
+/// # fn f(mut predictor: paddle_inference::Predictor) -> paddle_inference::Result<()> {
+/// let output = predictor.output("y")?;
+/// let (_, data) = output.as_slice::<f32>().unwrap();
+/// predictor.run()?;
+/// println!("{:?}", data);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// 修改 shape、数据或 LoD 的方法可能重新分配底层内存，因此需要可变借用，在数据视图释放前无法调用：
+///
+/// ```compile_fail
+/// # fn f(mut predictor: paddle_inference::Predictor) -> paddle_inference::Result<()> {
+/// let mut input = predictor.input("x")?;
+/// let (_, data) = input.as_slice::<f32>().unwrap();
+/// input.reshape(&[1024, 1024]);
+/// println!("{:?}", data);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Tensor<'p> {
+    runtime: Runtime,
     ptr: *mut PD_Tensor,
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    model_name: Option<&'p str>,
+    _predictor: PhantomData<&'p Predictor>,
 }
 
-impl Tensor {
-    pub fn from_ptr(ptr: *mut PD_Tensor) -> Self {
-        Self { ptr }
+impl<'p> Tensor<'p> {
+    /// 封装由`runtime`创建的 Tensor，释放时调用`runtime`中的`PD_TensorDestroy`
+    ///
+    /// 调用者需要保证`ptr`在`'p`内有效，因此仅供[`crate::Predictor`]内部使用。`model_name`用于记录复制数据的指标
+    pub(crate) fn from_ptr(
+        runtime: &Runtime,
+        ptr: *mut PD_Tensor,
+        model_name: Option<&'p str>,
+    ) -> Self {
+        Self {
+            runtime: runtime.clone(),
+            ptr,
+            model_name,
+            _predictor: PhantomData,
+        }
     }
 }
 
-impl Tensor {
+impl Tensor<'_> {
     /// 设置维度信息
-    pub fn reshape(&self, shape: &[i32]) {
+    pub fn reshape(&mut self, shape: &[i32]) {
+        trace_span!("Tensor::reshape", name = %self.name(), shape = ?shape);
         call! {
-            PD_TensorReshape(self.ptr, shape.len(), shape.as_ptr() as *mut _)
+            &self.runtime => PD_TensorReshape(self.ptr, shape.len(), shape.as_ptr() as *mut _)
         };
     }
 
     /// 获取维度信息
     pub fn shape(&self) -> Vec<i32> {
-        let ptr = call! { PD_TensorGetShape(self.ptr) };
-        OneDimArrayInt32::from_ptr(ptr).to_vec()
+        let ptr = call! { &self.runtime => PD_TensorGetShape(self.ptr) };
+        OneDimArrayInt32::from_ptr(&self.runtime, ptr).to_vec()
     }
 
     pub fn data_type(&self) -> DataType {
-        call! { PD_TensorGetDataType(self.ptr) }
+        call! { &self.runtime => PD_TensorGetDataType(self.ptr) }
     }
 
-    pub fn name(&self) -> Cow<str> {
-        let ptr = call! { PD_TensorGetName(self.ptr) };
+    pub fn name(&self) -> Cow<'_, str> {
+        let ptr = call! { &self.runtime => PD_TensorGetName(self.ptr) };
         unsafe { CStr::from_ptr(ptr).to_string_lossy() }
     }
 }
 
-impl Tensor {
-    pub fn copy_from_f32(&self, data: &[f32]) {
-        call! {
-            PD_TensorCopyFromCpuFloat(self.ptr, data.as_ptr())
-        };
-    }
-
-    pub fn copy_from_i64(&self, data: &[i64]) {
-        call! {
-            PD_TensorCopyFromCpuInt64(self.ptr, data.as_ptr())
-        };
-    }
-
-    pub fn copy_from_i32(&self, data: &[i32]) {
-        call! {
-            PD_TensorCopyFromCpuInt32(self.ptr, data.as_ptr())
-        };
-    }
-
-    pub fn copy_from_u8(&self, data: &[u8]) {
-        call! {
-            PD_TensorCopyFromCpuUint8(self.ptr, data.as_ptr())
-        };
-    }
-
-    pub fn copy_from_i8(&self, data: &[i8]) {
-        call! {
-            PD_TensorCopyFromCpuInt8(self.ptr, data.as_ptr())
-        };
-    }
-}
-
-impl Tensor {
-    #[inline]
-    fn size(&self) -> usize {
-        self.shape().into_iter().fold(1usize, |s, i| s * i as usize)
-    }
-
-    fn check_data_type(&self, ty: DataType) -> bool {
-        let dt = self.data_type();
-        dt != DataType::Unknown && dt == ty
-    }
-
-    fn check(&self, size: usize, ty: DataType) -> bool {
-        size >= self.size() && self.check_data_type(ty)
-    }
-}
-
-impl Tensor {
-    /// 从 Tensor 中获取数据，返回是否获取成功
+impl Tensor<'_> {
+    /// 将数据复制到 Tensor 中，**需要先调用[`Self::reshape`]**
     ///
-    /// 如果出现以下情况则获取失败
-    /// - 输入类型和[`Self::data_type`]不匹配
-    /// - 输入数据大小小于[`Self::shape`]结果之积
-    pub fn copy_to_f32(&self, data: &mut [f32]) -> bool {
-        if self.check(data.len(), DataType::Float32) {
-            call! { PD_TensorCopyToCpuFloat(self.ptr, data.as_mut_ptr()) };
-            true
-        } else {
-            false
-        }
+    /// 如果输入数据大小小于[`Self::shape`]结果之积则复制失败
+    pub fn copy_from<T: Element>(&mut self, data: &[T]) -> Result<()> {
+        trace_span!(
+            "Tensor::copy_from",
+            name = %self.name(),
+            shape = ?self.shape(),
+            data_type = ?T::DATA_TYPE
+        );
+        self.check_len(data.len())?;
+        let copy_from = T::CopyFromCpu::load(&self.runtime)?;
+        #[cfg(feature = "metrics")]
+        let start = std::time::Instant::now();
+        unsafe { copy_from(self.ptr, data.as_ptr()) };
+        #[cfg(feature = "metrics")]
+        crate::metrics::observe_copy(self.model_name, "input", start.elapsed());
+        Ok(())
     }
 
-    /// 从 Tensor 中获取数据，返回是否获取成功
+    /// 从 Tensor 中获取数据
     ///
     /// 如果出现以下情况则获取失败
     /// - 输入类型和[`Self::data_type`]不匹配
     /// - 输入数据大小小于[`Self::shape`]结果之积
-    pub fn copy_to_i64(&self, data: &mut [i64]) -> bool {
-        if self.check(data.len(), DataType::Int64) {
-            call! { PD_TensorCopyToCpuInt64(self.ptr, data.as_mut_ptr()) };
-            true
-        } else {
-            false
-        }
+    pub fn copy_to<T: Element>(&self, data: &mut [T]) -> Result<()> {
+        trace_span!(
+            "Tensor::copy_to",
+            name = %self.name(),
+            shape = ?self.shape(),
+            data_type = ?self.data_type()
+        );
+        self.check(data.len(), T::DATA_TYPE)?;
+        let copy_to = T::CopyToCpu::load(&self.runtime)?;
+        #[cfg(feature = "metrics")]
+        let start = std::time::Instant::now();
+        unsafe { copy_to(self.ptr, data.as_mut_ptr()) };
+        #[cfg(feature = "metrics")]
+        crate::metrics::observe_copy(self.model_name, "output", start.elapsed());
+        Ok(())
     }
 
-    /// 从 Tensor 中获取数据，返回是否获取成功
+    /// 将 Tensor 中的数据复制到新的`Vec`中
     ///
-    /// 如果出现以下情况则获取失败
-    /// - 输入类型和[`Self::data_type`]不匹配
-    /// - 输入数据大小小于[`Self::shape`]结果之积
-    pub fn copy_to_i32(&self, data: &mut [i32]) -> bool {
-        if self.check(data.len(), DataType::Int32) {
-            call! { PD_TensorCopyToCpuInt32(self.ptr, data.as_mut_ptr()) };
-            true
-        } else {
-            false
-        }
+    /// 输入类型和[`Self::data_type`]不匹配时获取失败，shape 包含负数或元素数量溢出时返回
+    /// [`Error::InvalidShape`]
+    pub fn to_vec<T: Element>(&self) -> Result<Vec<T>> {
+        let mut data = vec![T::default(); self.size()?];
+        self.copy_to(&mut data)?;
+        Ok(data)
     }
 
-    /// 从 Tensor 中获取数据，返回是否获取成功
+    /// 将 Tensor 的 shape 及数据复制到新的[`HostTensor`]中
     ///
-    /// 如果出现以下情况则获取失败
-    /// - 输入类型和[`Self::data_type`]不匹配
-    /// - 输入数据大小小于[`Self::shape`]结果之积
-    pub fn copy_to_u8(&self, data: &mut [u8]) -> bool {
-        if self.check(data.len(), DataType::Uint8) {
-            call! { PD_TensorCopyToCpuUint8(self.ptr, data.as_mut_ptr()) };
-            true
-        } else {
-            false
-        }
+    /// [`Self::data_type`]为[`DataType::Unknown`]时获取失败，shape 无效时返回
+    /// [`Error::InvalidShape`]
+    pub fn to_host(&self) -> Result<HostTensor> {
+        let shape = self.shape();
+        let shape = shape
+            .iter()
+            .map(|&d| usize::try_from(d))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|_| Error::InvalidShape(shape.clone()))?;
+        let data = match self.data_type() {
+            DataType::Float32 => HostData::Float32(self.to_vec()?),
+            DataType::Int64 => HostData::Int64(self.to_vec()?),
+            DataType::Int32 => HostData::Int32(self.to_vec()?),
+            DataType::Uint8 => HostData::Uint8(self.to_vec()?),
+            DataType::Int8 => HostData::Int8(self.to_vec()?),
+            DataType::Unknown => return Err(Error::UnknownDataType(self.name().into_owned())),
+        };
+        HostTensor::from_data(shape, data)
     }
 
-    /// 从 Tensor 中获取数据，返回是否获取成功
-    ///
-    /// 如果出现以下情况则获取失败
-    /// - 输入类型和[`Self::data_type`]不匹配
-    /// - 输入数据大小小于[`Self::shape`]结果之积
-    pub fn copy_to_i8(&self, data: &mut [i8]) -> bool {
-        if self.check(data.len(), DataType::Uint8) {
-            call! { PD_TensorCopyToCpuInt8(self.ptr, data.as_mut_ptr()) };
-            true
-        } else {
-            false
+    /// 按照`host`的 shape 调用[`Self::reshape`]，并将数据复制到 Tensor 中
+    pub fn copy_from_host(&mut self, host: &HostTensor) -> Result<()> {
+        let shape = host.shape().iter().map(|&s| s as i32).collect::<Vec<_>>();
+        self.reshape(&shape);
+        match host.data() {
+            HostData::Float32(data) => self.copy_from(data),
+            HostData::Int64(data) => self.copy_from(data),
+            HostData::Int32(data) => self.copy_from(data),
+            HostData::Uint8(data) => self.copy_from(data),
+            HostData::Int8(data) => self.copy_from(data),
         }
     }
-}
 
-impl Tensor {
     /// 获取 Tensor 底层数据，用于设置输入数据。
     ///
     /// **需要先调用[`Self::reshape`]**
     ///
     /// 如果底层数据类型([`DataType`])不对应则返回`None`
-    pub fn as_mut_slice_f32(&self, place_type: PlaceType) -> Option<&mut [f32]> {
-        self.check_data_type(DataType::Float32).then(|| {
-            let ptr = call! { PD_TensorMutableDataFloat(self.ptr, place_type) };
-            unsafe { std::slice::from_raw_parts_mut(ptr, self.size()) }
+    pub fn as_mut_slice<T: Element>(&mut self, place_type: PlaceType) -> Option<&mut [T]> {
+        let mutable_data = T::MutableData::load(&self.runtime).ok()?;
+        self.check_data_type(T::DATA_TYPE).then(|| {
+            let ptr = unsafe { mutable_data(self.ptr, place_type) };
+            let size = self.size().unwrap_or(0);
+            if ptr.is_null() || size == 0 {
+                &mut []
+            } else {
+                unsafe { std::slice::from_raw_parts_mut(ptr, size) }
+            }
         })
     }
 
-    /// 获取 Tensor 底层数据，用于设置输入数据。
-    ///
-    /// **需要先调用[`Self::reshape`]**
-    ///
-    /// 如果底层数据类型([`DataType`])不对应则返回`None`
-    pub fn as_mut_slice_i64(&self, place_type: PlaceType) -> Option<&mut [i64]> {
-        self.check_data_type(DataType::Int64).then(|| {
-            let ptr = call! { PD_TensorMutableDataInt64(self.ptr, place_type) };
-            unsafe { std::slice::from_raw_parts_mut(ptr, self.size()) }
-        })
-    }
-
-    /// 获取 Tensor 底层数据，用于设置输入数据。
-    ///
-    /// **需要先调用[`Self::reshape`]**
+    /// 获取 Tensor 底层数据，用于读取输出数据。
     ///
     /// 如果底层数据类型([`DataType`])不对应则返回`None`
-    pub fn as_mut_slice_i32(&self, place_type: PlaceType) -> Option<&mut [i32]> {
-        self.check_data_type(DataType::Int32).then(|| {
-            let ptr = call! { PD_TensorMutableDataInt32(self.ptr, place_type) };
-            unsafe { std::slice::from_raw_parts_mut(ptr, self.size()) }
+    pub fn as_slice<T: Element>(&self) -> Option<(PlaceType, &[T])> {
+        let data = T::Data::load(&self.runtime).ok()?;
+        self.check_data_type(T::DATA_TYPE).then(|| {
+            let mut place_type = PlaceType::Unknown;
+            let mut size = 0;
+            let ptr = unsafe { data(self.ptr, &mut place_type, &mut size) };
+            let s = if ptr.is_null() || size <= 0 {
+                &[]
+            } else {
+                unsafe { std::slice::from_raw_parts(ptr, size as usize) }
+            };
+            (place_type, s)
         })
     }
+}
 
-    /// 获取 Tensor 底层数据，用于设置输入数据。
-    ///
-    /// **需要先调用[`Self::reshape`]**
-    ///
-    /// 如果底层数据类型([`DataType`])不对应则返回`None`
-    pub fn as_mut_slice_u8(&self, place_type: PlaceType) -> Option<&mut [u8]> {
-        self.check_data_type(DataType::Uint8).then(|| {
-            let ptr = call! { PD_TensorMutableDataUint8(self.ptr, place_type) };
-            unsafe { std::slice::from_raw_parts_mut(ptr, self.size()) }
-        })
-    }
+macro_rules! typed {
+    ($( $ty: ty => $copy_from: ident, $copy_to: ident, $as_mut_slice: ident, $as_slice: ident; )*) => {
+        impl Tensor<'_> {
+            $(
+                #[doc = concat!("等同于[`Self::copy_from`]`::<", stringify!($ty), ">`")]
+                #[deprecated(since = "0.5.0", note = "请使用`copy_from`")]
+                pub fn $copy_from(&mut self, data: &[$ty]) -> Result<()> {
+                    self.copy_from(data)
+                }
+
+                #[doc = concat!("等同于[`Self::copy_to`]`::<", stringify!($ty), ">`")]
+                #[deprecated(since = "0.5.0", note = "请使用`copy_to`")]
+                pub fn $copy_to(&self, data: &mut [$ty]) -> Result<()> {
+                    self.copy_to(data)
+                }
+
+                #[doc = concat!("等同于[`Self::as_mut_slice`]`::<", stringify!($ty), ">`")]
+                #[deprecated(since = "0.5.0", note = "请使用`as_mut_slice`")]
+                pub fn $as_mut_slice(&mut self, place_type: PlaceType) -> Option<&mut [$ty]> {
+                    self.as_mut_slice(place_type)
+                }
+
+                #[doc = concat!("等同于[`Self::as_slice`]`::<", stringify!($ty), ">`")]
+                #[deprecated(since = "0.5.0", note = "请使用`as_slice`")]
+                pub fn $as_slice(&self) -> Option<(PlaceType, &[$ty])> {
+                    self.as_slice()
+                }
+            )*
+        }
+    };
+}
 
-    /// 获取 Tensor 底层数据，用于设置输入数据。
-    ///
-    /// **需要先调用[`Self::reshape`]**
-    ///
-    /// 如果底层数据类型([`DataType`])不对应则返回`None`
-    pub fn as_mut_slice_i8(&self, place_type: PlaceType) -> Option<&mut [i8]> {
-        self.check_data_type(DataType::Uint8).then(|| {
-            let ptr = call! { PD_TensorMutableDataInt8(self.ptr, place_type) };
-            unsafe { std::slice::from_raw_parts_mut(ptr, self.size()) }
-        })
-    }
+typed! {
+    f32 => copy_from_f32, copy_to_f32, as_mut_slice_f32, as_slice_f32;
+    i64 => copy_from_i64, copy_to_i64, as_mut_slice_i64, as_slice_i64;
+    i32 => copy_from_i32, copy_to_i32, as_mut_slice_i32, as_slice_i32;
+    u8 => copy_from_u8, copy_to_u8, as_mut_slice_u8, as_slice_u8;
+    i8 => copy_from_i8, copy_to_i8, as_mut_slice_i8, as_slice_i8;
 }
 
-impl Tensor {
-    /// 获取 Tensor 底层数据，用于读取输出数据。
-    ///
-    /// 如果底层数据类型([`DataType`])不对应则返回`None`
-    pub fn as_slice_f32(&self) -> Option<(PlaceType, &[f32])> {
-        self.check_data_type(DataType::Float32).then(|| {
-            let mut place_type = PlaceType::Unknown;
-            let mut size = 0;
-            let ptr = call! { PD_TensorDataFloat(self.ptr, &mut place_type, &mut size) };
-            let s = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
-            (place_type, s)
-        })
+impl Tensor<'_> {
+    /// 元素数量，shape 包含负数或元素数量溢出时返回[`Error::InvalidShape`]
+    fn size(&self) -> Result<usize> {
+        let shape = self.shape();
+        shape
+            .iter()
+            .try_fold(1usize, |s, &d| s.checked_mul(usize::try_from(d).ok()?))
+            .ok_or(Error::InvalidShape(shape))
     }
 
-    /// 获取 Tensor 底层数据，用于读取输出数据。
-    ///
-    /// 如果底层数据类型([`DataType`])不对应则返回`None`
-    pub fn as_slice_i64(&self) -> Option<(PlaceType, &[i64])> {
-        self.check_data_type(DataType::Int64).then(|| {
-            let mut place_type = PlaceType::Unknown;
-            let mut size = 0;
-            let ptr = call! { PD_TensorDataInt64(self.ptr, &mut place_type, &mut size) };
-            let s = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
-            (place_type, s)
-        })
+    fn check_data_type(&self, ty: DataType) -> bool {
+        let dt = self.data_type();
+        dt != DataType::Unknown && dt == ty
     }
 
-    /// 获取 Tensor 底层数据，用于读取输出数据。
-    ///
-    /// 如果底层数据类型([`DataType`])不对应则返回`None`
-    pub fn as_slice_i32(&self) -> Option<(PlaceType, &[i32])> {
-        self.check_data_type(DataType::Int32).then(|| {
-            let mut place_type = PlaceType::Unknown;
-            let mut size = 0;
-            let ptr = call! { PD_TensorDataInt32(self.ptr, &mut place_type, &mut size) };
-            let s = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
-            (place_type, s)
-        })
+    fn check_len(&self, len: usize) -> Result<()> {
+        if len >= self.size()? {
+            Ok(())
+        } else {
+            Err(Error::ShapeMismatch {
+                shape: self.shape(),
+                len,
+            })
+        }
     }
 
-    /// 获取 Tensor 底层数据，用于读取输出数据。
-    ///
-    /// 如果底层数据类型([`DataType`])不对应则返回`None`
-    pub fn as_slice_u8(&self) -> Option<(PlaceType, &[u8])> {
-        self.check_data_type(DataType::Uint8).then(|| {
-            let mut place_type = PlaceType::Unknown;
-            let mut size = 0;
-            let ptr = call! { PD_TensorDataUint8(self.ptr, &mut place_type, &mut size) };
-            let s = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
-            (place_type, s)
-        })
+    fn check(&self, len: usize, ty: DataType) -> Result<()> {
+        if !self.check_data_type(ty) {
+            return Err(Error::DataTypeMismatch {
+                expected: ty,
+                actual: self.data_type(),
+            });
+        }
+        self.check_len(len)
     }
+}
 
-    /// 获取 Tensor 底层数据，用于读取输出数据。
+impl Tensor<'_> {
+    /// 设置 LoD 信息，**需要先调用[`Self::reshape`]**
     ///
-    /// 如果底层数据类型([`DataType`])不对应则返回`None`
-    pub fn as_slice_i8(&self) -> Option<(PlaceType, &[i8])> {
-        self.check_data_type(DataType::Uint8).then(|| {
-            let mut place_type = PlaceType::Unknown;
-            let mut size = 0;
-            let ptr = call! { PD_TensorDataInt8(self.ptr, &mut place_type, &mut size) };
-            let s = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
-            (place_type, s)
-        })
+    /// LoD 非空且最后一个偏移量与第一维的大小不相等时返回[`Error::InvalidLod`]
+    pub fn set_lod(&mut self, lod: &Lod) -> Result<()> {
+        if !lod.is_empty() {
+            let batch = self.shape().first().map_or(0, |&d| d.max(0) as usize);
+            lod.check(batch)?;
+        }
+        let array = TwoDimArraySize::from(Vec::from(lod.clone()));
+        try_call! { &self.runtime => PD_TensorSetLod(self.ptr, array.ptr) };
+        Ok(())
     }
-}
 
-impl Tensor {
-    pub fn set_lod(&self, lod: TwoDimArraySize) {
-        call! { PD_TensorSetLod(self.ptr, lod.ptr) };
+    /// 获取 LoD 信息
+    pub fn lod(&self) -> Lod {
+        let ptr = call! { &self.runtime => PD_TensorGetLod(self.ptr) };
+        Lod::from_raw(TwoDimArraySize::from_ptr(&self.runtime, ptr).into())
     }
 
-    pub fn lod(&self) -> TwoDimArraySize {
-        let ptr = call!(PD_TensorGetLod(self.ptr));
-        TwoDimArraySize::from_ptr(ptr)
+    /// 将变长序列拼接后复制到 Tensor 中，shape 设置为`[序列总长度, 1]`，并设置对应的 LoD 信息
+    pub fn copy_from_sequences<T: Element, S: AsRef<[T]>>(
+        &mut self,
+        sequences: &[S],
+    ) -> Result<()> {
+        let (data, lod) = Lod::pack(sequences);
+        self.reshape(&[data.len() as i32, 1]);
+        self.copy_from(&data)?;
+        self.set_lod(&lod)
     }
 }
 
-impl Drop for Tensor {
+impl Drop for Tensor<'_> {
     fn drop(&mut self) {
-        call!(PD_TensorDestroy(self.ptr));
+        call! { &self.runtime => PD_TensorDestroy(self.ptr) };
     }
 }
@@ -0,0 +1,111 @@
+#![cfg(feature = "async")]
+
+mod common;
+
+use common::{add_inputs, config, ADD_MODEL};
+use paddle_inference::{AsyncPredictor, Error, HostTensor, NamedTensors, Result};
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn test_run() {
+    let predictor = AsyncPredictor::new(config(ADD_MODEL), 2, 8).unwrap();
+    let results = run_all(&predictor, 4).await;
+    for (i, outputs) in results.into_iter().enumerate() {
+        let outputs = outputs.unwrap();
+        assert_eq!(outputs[0].0, "sum");
+        assert_eq!(outputs[0].1.as_slice::<f32>(), Some(&[i as f32 + 1.0][..]));
+    }
+
+    assert_eq!(
+        predictor
+            .run(vec![(
+                "z".to_string(),
+                HostTensor::zeros::<f32>(vec![1]).unwrap()
+            )])
+            .await,
+        Err(Error::TensorNotFound("z".to_string()))
+    );
+}
+
+/// 先提交所有任务再依次等待结果
+async fn run_all(predictor: &AsyncPredictor, n: usize) -> Vec<Result<NamedTensors>> {
+    let pending = (0..n)
+        .map(|i| predictor.submit(add_inputs(&[1], i as f32)).unwrap())
+        .collect::<Vec<_>>();
+    let mut results = vec![];
+    for p in pending {
+        results.push(p.await);
+    }
+    results
+}
+
+#[tokio::test]
+async fn test_queue_full() {
+    let script = "input x float32\ninput y float32\noutput sum = sleep x 200";
+    let predictor = AsyncPredictor::new(config(script), 1, 1).unwrap();
+
+    let first = predictor.submit(add_inputs(&[1], 0.0)).unwrap();
+    // 等待工作线程取出第一个任务
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let second = predictor.submit(add_inputs(&[1], 1.0)).unwrap();
+    assert!(matches!(
+        predictor.submit(add_inputs(&[1], 2.0)),
+        Err(Error::QueueFull)
+    ));
+
+    assert!(first.await.is_ok());
+    assert!(second.await.is_ok());
+}
+
+#[tokio::test]
+async fn test_zero_capacity() {
+    // 容量为 0 时按 1 处理，而不是创建无法缓存任务的队列
+    let predictor = AsyncPredictor::new(config(ADD_MODEL), 1, 0).unwrap();
+    let pending = predictor.submit(add_inputs(&[1], 1.0)).unwrap();
+    assert!(pending.await.is_ok());
+}
+
+#[tokio::test]
+async fn test_clone_failed() {
+    let script = format!("{}max_clones 1", ADD_MODEL);
+    assert_eq!(
+        AsyncPredictor::new(config(&script), 3, 8).err(),
+        Some(Error::NullHandle("PD_PredictorClone"))
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_drop_without_blocking() {
+    let script = "input x float32\ninput y float32\noutput sum = sleep x 300";
+    let predictor = AsyncPredictor::new(config(script), 1, 1).unwrap();
+    let pending = predictor.submit(add_inputs(&[1], 1.0)).unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // 工作线程正在预测时释放不会阻塞运行时，已提交的任务仍会完成
+    let start = Instant::now();
+    drop(predictor);
+    assert!(start.elapsed() < Duration::from_millis(100));
+    assert!(pending.await.is_ok());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_shutdown() {
+    let script = "input x float32\ninput y float32\noutput sum = sleep x 100";
+    let predictor = AsyncPredictor::new(config(script), 2, 4).unwrap();
+    let pending = (0..2)
+        .map(|i| predictor.submit(add_inputs(&[1], i as f32)).unwrap())
+        .collect::<Vec<_>>();
+
+    let start = Instant::now();
+    let ticker = tokio::spawn(async {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        Instant::now()
+    });
+    predictor.shutdown().await;
+    // 等待工作线程期间运行时可以执行其它任务
+    assert!(ticker.await.unwrap() - start < Duration::from_millis(80));
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    for p in pending {
+        assert!(p.await.is_ok());
+    }
+}
@@ -0,0 +1,156 @@
+mod common;
+
+use common::{config, ADD_MODEL};
+use paddle_inference::{BatchOptions, Batcher, CropDim, HostTensor, NamedTensors};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn sample(x: &[f32], y: &[f32]) -> NamedTensors {
+    vec![
+        (
+            "x".to_string(),
+            HostTensor::new(vec![x.len()], x.to_vec()).unwrap(),
+        ),
+        (
+            "y".to_string(),
+            HostTensor::new(vec![y.len()], y.to_vec()).unwrap(),
+        ),
+    ]
+}
+
+#[test]
+fn test_batch() {
+    // 每次预测耗时 200ms，4 个请求合并为一个批次时总耗时远小于 800ms
+    let script = format!("{}\noutput slow = sleep x 200", ADD_MODEL);
+    let batcher = Arc::new(Batcher::new(
+        config(&script).build().unwrap(),
+        BatchOptions {
+            max_batch_size: 4,
+            max_wait: Duration::from_millis(200),
+            padding: false,
+            crop: vec![],
+        },
+    ));
+
+    let start = Instant::now();
+    let handles = (0..4)
+        .map(|i| {
+            let batcher = batcher.clone();
+            std::thread::spawn(move || {
+                batcher
+                    .run(sample(&[i as f32, 1.0], &[10.0, 20.0]))
+                    .unwrap()
+            })
+        })
+        .collect::<Vec<_>>();
+    for (i, handle) in handles.into_iter().enumerate() {
+        let outputs = handle.join().unwrap();
+        assert_eq!(outputs[0].0, "sum");
+        assert_eq!(outputs[0].1.shape(), [2]);
+        assert_eq!(
+            outputs[0].1.as_slice::<f32>().unwrap(),
+            [i as f32 + 10.0, 21.0]
+        );
+    }
+    assert!(start.elapsed() < Duration::from_millis(600));
+}
+
+#[test]
+fn test_padding() {
+    let batcher = Arc::new(Batcher::new(
+        config(ADD_MODEL).build().unwrap(),
+        BatchOptions {
+            max_batch_size: 2,
+            max_wait: Duration::from_millis(200),
+            padding: true,
+            crop: vec![
+                CropDim::new("sum", 0, "x", 0),
+                CropDim::new("copy", 0, "x", 0),
+            ],
+        },
+    ));
+
+    let other = batcher.clone();
+    let handle = std::thread::spawn(move || other.run(sample(&[1.0], &[2.0])).unwrap());
+    let outputs = batcher
+        .run(sample(&[1.0, 2.0, 3.0], &[1.0, 1.0, 1.0]))
+        .unwrap();
+    assert_eq!(outputs[0].1.as_slice::<f32>().unwrap(), [2.0, 3.0, 4.0]);
+
+    // 输出被裁剪为原始的 shape
+    let outputs = handle.join().unwrap();
+    assert_eq!(outputs[0].1.shape(), [1]);
+    assert_eq!(outputs[0].1.as_slice::<f32>().unwrap(), [3.0]);
+    assert_eq!(outputs[1].1.as_slice::<f32>().unwrap(), [1.0]);
+}
+
+#[test]
+fn test_padding_without_crop() {
+    let script = "
+input x float32
+input y float32
+output x_out = identity x
+output y_out = identity y
+";
+    let batcher = Arc::new(Batcher::new(
+        config(script).build().unwrap(),
+        BatchOptions {
+            max_batch_size: 2,
+            max_wait: Duration::from_millis(200),
+            padding: true,
+            crop: vec![CropDim::new("x_out", 0, "x", 0)],
+        },
+    ));
+
+    let other = batcher.clone();
+    let handle = std::thread::spawn(move || other.run(sample(&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0])));
+    let outputs = batcher.run(sample(&[1.0], &[7.0, 8.0])).unwrap();
+    assert_eq!(outputs[0].1.as_slice::<f32>().unwrap(), [1.0]);
+    // `y_out`的大小等于填充后`x`的长度，但未声明裁剪，因此保持填充后的大小
+    assert_eq!(outputs[1].1.as_slice::<f32>().unwrap(), [7.0, 8.0, 0.0]);
+    handle.join().unwrap().unwrap();
+}
+
+#[test]
+fn test_incompatible_not_counted() {
+    // 不兼容的请求不计入批次大小，兼容的请求不足时等待到`max_wait`
+    let batcher = Arc::new(Batcher::new(
+        config(ADD_MODEL).build().unwrap(),
+        BatchOptions {
+            max_batch_size: 2,
+            max_wait: Duration::from_millis(300),
+            padding: false,
+            crop: vec![],
+        },
+    ));
+
+    let run = |x: Vec<f32>, delay: u64| {
+        let batcher = batcher.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(delay));
+            let start = Instant::now();
+            let outputs = batcher.run(sample(&x, &x)).unwrap();
+            (outputs, start.elapsed())
+        })
+    };
+    let first = run(vec![1.0], 0);
+    let incompatible = run(vec![1.0, 2.0], 50);
+    let second = run(vec![2.0], 150);
+
+    let (outputs, elapsed) = first.join().unwrap();
+    assert_eq!(outputs[0].1.as_slice::<f32>().unwrap(), [2.0]);
+    // 第二个兼容的请求到达后才执行，而不是在不兼容的请求到达时提前执行
+    assert!(elapsed >= Duration::from_millis(140), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_millis(290), "{:?}", elapsed);
+    let (second, _) = second.join().unwrap();
+    assert_eq!(second[0].1.as_slice::<f32>().unwrap(), [4.0]);
+    let (incompatible, _) = incompatible.join().unwrap();
+    assert_eq!(incompatible[0].1.as_slice::<f32>().unwrap(), [2.0, 4.0]);
+}
+
+#[test]
+fn test_batch_failed() {
+    let batcher = Batcher::new(config(ADD_MODEL).build().unwrap(), BatchOptions::default());
+    assert!(batcher.run(sample(&[1.0], &[1.0, 2.0])).is_err());
+    assert!(batcher.run(sample(&[1.0], &[2.0])).is_ok());
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::{config, ADD_MODEL};
+use paddle_inference::common::DataType;
+use paddle_inference::{benchmark, BenchInput, BenchOptions, Error};
+
+#[test]
+fn test_benchmark() {
+    let options = BenchOptions {
+        inputs: vec![
+            BenchInput::new("x", vec![2, 3]),
+            BenchInput::new("y", vec![2, 3]).data_type(DataType::Float32),
+        ],
+        warmup: 2,
+        iterations: 20,
+        threads: 1,
+    };
+    let report = benchmark(config(ADD_MODEL), &options).unwrap();
+    assert_eq!(report.threads, 1);
+    assert_eq!(report.iterations, 20);
+    assert!(report.throughput > 0.0);
+    let latency = report.latency;
+    assert!(latency.min <= latency.p50 && latency.p50 <= latency.p90);
+    assert!(latency.p90 <= latency.p99 && latency.p99 <= latency.max);
+    assert!(report.to_string().contains("p99"));
+    #[cfg(target_os = "linux")]
+    assert!(report.peak_rss.unwrap() > 0);
+}
+
+#[test]
+fn test_benchmark_parallel() {
+    let script = "input x float32\noutput y = sleep x 50";
+    let options = BenchOptions {
+        inputs: vec![BenchInput::new("x", vec![1])],
+        warmup: 0,
+        iterations: 3,
+        threads: 4,
+    };
+    let report = benchmark(config(script), &options).unwrap();
+    assert_eq!(report.threads, 4);
+    assert_eq!(report.iterations, 12);
+    assert!(report.latency.min >= 50.0);
+    // 每个预测器依次运行 3 次，总耗时不少于 3 次预测的耗时
+    assert!(
+        report.elapsed * 1000.0 >= 3.0 * report.latency.min,
+        "{}",
+        report
+    );
+}
+
+#[test]
+fn test_benchmark_error() {
+    let options = BenchOptions {
+        inputs: vec![BenchInput::new("x", vec![2])],
+        ..Default::default()
+    };
+    // 缺少输入`y`
+    assert_eq!(
+        benchmark(config(ADD_MODEL), &options).unwrap_err(),
+        Error::RunFailed
+    );
+    let options = BenchOptions {
+        inputs: vec![BenchInput::new("z", vec![2])],
+        ..Default::default()
+    };
+    assert_eq!(
+        benchmark(config(ADD_MODEL), &options).unwrap_err(),
+        Error::TensorNotFound("z".to_string())
+    );
+    // shape 之积溢出
+    let options = BenchOptions {
+        inputs: vec![BenchInput::new("x", vec![usize::MAX, 2])],
+        ..Default::default()
+    };
+    assert!(matches!(
+        benchmark(config(ADD_MODEL), &options),
+        Err(Error::InvalidShape(_))
+    ));
+}
+
+#[test]
+fn test_clone_failed() {
+    let options = BenchOptions {
+        inputs: vec![BenchInput::new("x", vec![1]), BenchInput::new("y", vec![1])],
+        threads: 3,
+        ..Default::default()
+    };
+    let script = format!("{}max_clones 1", ADD_MODEL);
+    assert_eq!(
+        benchmark(config(&script), &options).err(),
+        Some(Error::NullHandle("PD_PredictorClone"))
+    );
+}
@@ -0,0 +1,51 @@
+//! 集成测试共用的函数
+//!
+//! 测试使用`mock`目录下的模拟动态库，该库作为 dev-dependency 编译到测试程序所在的目录中
+#![allow(dead_code)]
+
+use paddle_inference::config::model::Model;
+use paddle_inference::config::Config;
+use paddle_inference::{HostTensor, NamedTensors, Runtime};
+use std::path::PathBuf;
+
+/// 两个输入、两个输出的模型
+pub const ADD_MODEL: &str = "
+input x float32
+input y float32
+output sum = add x y
+output copy = identity x
+";
+
+/// [`ADD_MODEL`]的输入，shape 均为`shape`，`x`的所有元素为`x`，`y`的所有元素为`1.0`
+pub fn add_inputs(shape: &[usize], x: f32) -> NamedTensors {
+    let len = shape.iter().product();
+    let tensor = |v: f32| HostTensor::new(shape.to_vec(), vec![v; len]).unwrap();
+    vec![("x".to_string(), tensor(x)), ("y".to_string(), tensor(1.0))]
+}
+
+/// 加载模拟动态库
+pub fn runtime() -> Runtime {
+    let exe = std::env::current_exe().unwrap();
+    let dir = exe.parent().unwrap();
+    Runtime::search([dir, dir.parent().unwrap()]).unwrap()
+}
+
+/// 使用内存中的模型脚本及模拟动态库创建配置
+pub fn config(script: &str) -> Config {
+    Config::new(Model::Memory {
+        model: script.as_bytes().to_vec(),
+        params: vec![],
+    })
+    .runtime(runtime())
+}
+
+/// 在临时目录中创建一个测试独占的目录
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "paddle_inference_test_{}_{}",
+        std::process::id(),
+        name
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
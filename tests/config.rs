@@ -0,0 +1,222 @@
+mod common;
+
+use common::{config, runtime, temp_dir, ADD_MODEL};
+use paddle_inference::common::PrecisionType;
+use paddle_inference::config::model::Model;
+use paddle_inference::config::passes::Passes;
+use paddle_inference::config::setting::{Cpu, Gpu, Mkldnn, TensorRT, TunedDynamicShape};
+use paddle_inference::config::Config;
+use paddle_inference::Error;
+
+#[test]
+fn test_capabilities() {
+    let runtime = runtime();
+    let capabilities = runtime.capabilities();
+    assert_eq!(capabilities.missing().count(), 0);
+    assert_eq!(
+        capabilities.version.map(|v| v.to_string()),
+        Some("2.4.2".to_string())
+    );
+}
+
+#[test]
+fn test_build_from_memory() {
+    let predictor = config(ADD_MODEL).build().unwrap();
+    assert_eq!(predictor.input_num(), 2);
+    assert_eq!(predictor.output_num(), 2);
+    assert_eq!(Vec::<String>::from(predictor.input_names()), ["x", "y"]);
+    assert_eq!(
+        Vec::<String>::from(predictor.output_names()),
+        ["sum", "copy"]
+    );
+}
+
+#[test]
+fn test_build_from_file() {
+    let dir = temp_dir("build_from_file");
+    std::fs::write(dir.join("__model__"), ADD_MODEL).unwrap();
+    std::fs::write(dir.join("model.pdmodel"), ADD_MODEL).unwrap();
+
+    let predictor = Config::new(Model::dir(dir.display()))
+        .runtime(runtime())
+        .build()
+        .unwrap();
+    assert_eq!(predictor.input_num(), 2);
+
+    let predictor = Config::new(Model::path(
+        dir.join("model.pdmodel").display().to_string(),
+        dir.join("model.pdiparams").display().to_string(),
+    ))
+    .runtime(runtime())
+    .build()
+    .unwrap();
+    assert_eq!(predictor.output_num(), 2);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_build_error() {
+    assert!(matches!(
+        Config::new(Model::dir("/paddle_inference_model_not_exist"))
+            .runtime(runtime())
+            .build(),
+        Err(Error::NullHandle("PD_PredictorCreate"))
+    ));
+    assert!(matches!(
+        config("not a model").build(),
+        Err(Error::NullHandle("PD_PredictorCreate"))
+    ));
+}
+
+#[test]
+fn test_unsupported() {
+    let runtime = runtime();
+    let mut capabilities = runtime.capabilities().clone();
+    capabilities
+        .functions
+        .insert("PD_ConfigEnableUseGpu".to_string(), false);
+
+    let config = config(ADD_MODEL).gpu(Gpu {
+        memory_pool_init_size_mb: 100,
+        device_id: 0,
+        enable_multi_stream: false,
+        enable_cudnn: false,
+        enable_tensor_rt: None,
+    });
+    assert!(config.check(runtime.capabilities()).is_ok());
+    assert_eq!(
+        config.check(&capabilities).unwrap_err(),
+        Error::Unsupported {
+            option: "gpu",
+            function: "PD_ConfigEnableUseGpu"
+        }
+    );
+}
+
+#[test]
+fn test_config_report() {
+    let predictor = config(ADD_MODEL)
+        .cpu(Cpu {
+            threads: Some(4),
+            mkldnn: None,
+        })
+        .ir_optimization(false)
+        .enable_memory_optimization()
+        .build()
+        .unwrap();
+
+    let report = predictor.config_report();
+    assert_eq!(report.model_from_memory, Some(true));
+    assert_eq!(report.use_gpu, Some(false));
+    assert_eq!(report.cpu_math_library_num_threads, Some(4));
+    assert_eq!(report.ir_optimization, Some(false));
+    assert_eq!(report.memory_optimization, Some(true));
+    let summary = report.summary.as_deref().unwrap();
+    assert!(summary.contains("PD_ConfigSetCpuMathLibraryNumThreads(4)"));
+    assert!(summary.contains("PD_ConfigSwitchIrOptim(false)"));
+
+    assert_eq!(predictor.clone().config_report(), report);
+}
+
+#[test]
+fn test_mkldnn() {
+    let predictor = config(ADD_MODEL)
+        .cpu(Cpu {
+            threads: None,
+            mkldnn: Some(Mkldnn {
+                cache_size: Some(10),
+                ..Default::default()
+            }),
+        })
+        .build()
+        .unwrap();
+
+    let report = predictor.config_report();
+    assert_eq!(report.mkldnn, Some(true));
+    let summary = report.summary.as_deref().unwrap();
+    let enable = summary.find("PD_ConfigEnableMKLDNN()").unwrap();
+    assert!(enable < summary.find("PD_ConfigSetMkldnnCacheCapacity(10)").unwrap());
+}
+
+#[test]
+fn test_passes() {
+    let config = config(ADD_MODEL);
+    let default = config.all_passes().unwrap();
+    assert!(default.iter().any(|p| p == "fc_fuse_pass"));
+
+    let config = config.passes(
+        Passes::default()
+            .delete("fc_fuse_pass")
+            .insert(0, "first_pass")
+            .append("last_pass"),
+    );
+    let passes = config.all_passes().unwrap();
+    assert!(!passes.iter().any(|p| p == "fc_fuse_pass"));
+    assert_eq!(passes.first().map(String::as_str), Some("first_pass"));
+    assert_eq!(passes.last().map(String::as_str), Some("last_pass"));
+    assert_eq!(passes.len(), default.len() + 1);
+
+    let predictor = config.build().unwrap();
+    assert_eq!(predictor.config_report().passes.as_ref(), Some(&passes));
+}
+
+#[test]
+fn test_tuned_dynamic_shape() {
+    let dir = temp_dir("tuned_dynamic_shape");
+    let path = dir.join("shape_range_info.pbtxt").display().to_string();
+
+    let mut predictor = config(ADD_MODEL)
+        .collect_shape_range_info(&path)
+        .build()
+        .unwrap();
+    let report = predictor.config_report();
+    assert_eq!(report.shape_range_info_collected, Some(true));
+    assert_eq!(report.shape_range_info_path.as_deref(), Some(path.as_str()));
+    for batch in [1, 4, 2] {
+        for name in ["x", "y"] {
+            let mut tensor = predictor.input(name).unwrap();
+            tensor.reshape(&[batch, 3]);
+            tensor.copy_from(&vec![0.0f32; batch as usize * 3]).unwrap();
+        }
+        predictor.run().unwrap();
+    }
+    drop(predictor);
+
+    let info = std::fs::read_to_string(&path).unwrap();
+    assert!(info.contains("name: \"x\""));
+    assert!(info.contains("max_shape: 4"));
+
+    let predictor = config(ADD_MODEL)
+        .gpu(Gpu {
+            memory_pool_init_size_mb: 100,
+            device_id: 0,
+            enable_multi_stream: false,
+            enable_cudnn: false,
+            enable_tensor_rt: Some(TensorRT {
+                workspace_size: 1 << 20,
+                max_batch_size: 4,
+                min_subgraph_size: 3,
+                precision_type: PrecisionType::Float32,
+                use_static: false,
+                use_calib_mode: false,
+                dynamic_shape_info: vec![],
+                disable_plugin_fp16: false,
+                enable_oss: false,
+                dla_core: None,
+                tuned_dynamic_shape: Some(TunedDynamicShape {
+                    shape_range_info_path: path.clone(),
+                    allow_build_at_runtime: true,
+                }),
+            }),
+        })
+        .build()
+        .unwrap();
+    let report = predictor.config_report();
+    assert_eq!(report.tensor_rt, Some(true));
+    assert_eq!(report.tensor_rt_tuned_dynamic_shape, Some(true));
+    assert_eq!(report.tensor_rt_allow_build_at_runtime, Some(true));
+    assert_eq!(report.shape_range_info_path, Some(path));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "metrics")]
+
+mod common;
+
+use common::{add_inputs, config, ADD_MODEL};
+use paddle_inference::{metrics, HostTensor};
+use std::io::{Read, Write};
+
+/// 获取名称及标签完全匹配的指标的值
+fn value(text: &str, metric: &str) -> f64 {
+    text.lines()
+        .find_map(|line| line.strip_prefix(metric)?.trim().parse().ok())
+        .unwrap_or_else(|| panic!("不存在指标`{}`:\n{}", metric, text))
+}
+
+#[test]
+fn test_metrics() {
+    let mut predictor = config(ADD_MODEL).model_name("metrics").build().unwrap();
+    assert_eq!(predictor.model_name(), Some("metrics"));
+    predictor.run_host(&add_inputs(&[4, 1], 1.0)).unwrap();
+    predictor
+        .clone()
+        .run_host(&add_inputs(&[2, 1], 1.0))
+        .unwrap();
+    let x = HostTensor::new(vec![2], vec![1.0f32; 2]).unwrap();
+    predictor.input("x").unwrap().copy_from_host(&x).unwrap();
+    assert!(predictor.run().is_err());
+
+    let text = metrics::gather();
+    let label = r#"{model="metrics"}"#;
+    assert_eq!(
+        value(&text, &format!("paddle_inference_runs_total{}", label)),
+        3.0
+    );
+    assert_eq!(
+        value(
+            &text,
+            &format!("paddle_inference_run_failures_total{}", label)
+        ),
+        1.0
+    );
+    assert_eq!(
+        value(&text, &format!("paddle_inference_batch_size_sum{}", label)),
+        8.0
+    );
+    // 第三次预测时`y`仍为第一次预测设置的 4 个 float32
+    assert_eq!(
+        value(&text, &format!("paddle_inference_input_bytes_sum{}", label)),
+        ((4 + 4) + (2 + 2) + (2 + 4)) as f64 * 4.0
+    );
+    assert_eq!(
+        value(
+            &text,
+            &format!("paddle_inference_run_duration_seconds_count{}", label)
+        ),
+        3.0
+    );
+    // 每个 Tensor 的每次复制记录一次
+    assert_eq!(
+        value(
+            &text,
+            r#"paddle_inference_copy_duration_seconds_count{direction="input",model="metrics"}"#
+        ),
+        5.0
+    );
+    assert_eq!(
+        value(
+            &text,
+            r#"paddle_inference_copy_duration_seconds_count{direction="output",model="metrics"}"#
+        ),
+        4.0
+    );
+}
+
+#[test]
+fn test_metrics_serve() {
+    let mut predictor = config(ADD_MODEL).build().unwrap();
+    predictor.run_host(&add_inputs(&[1, 1], 1.0)).unwrap();
+
+    let (addr, _handle) = metrics::serve("127.0.0.1:0").unwrap();
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    stream
+        .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(
+        response.contains(r#"paddle_inference_runs_total{model="default"}"#),
+        "{}",
+        response
+    );
+}
+
+#[test]
+fn test_metrics_serve_idle_connection() {
+    let (addr, _handle) = metrics::serve("127.0.0.1:0").unwrap();
+    // 不发送请求的连接不会一直阻塞后续请求
+    let _idle = std::net::TcpStream::connect(addr).unwrap();
+    let start = std::time::Instant::now();
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    stream
+        .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    stream
+        .set_read_timeout(Some(std::time::Duration::from_secs(10)))
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+}
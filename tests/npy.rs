@@ -0,0 +1,83 @@
+mod common;
+
+use common::{config, temp_dir, ADD_MODEL};
+use paddle_inference::{npy, Error, HostTensor};
+
+#[test]
+fn test_npy_replay() {
+    let dir = temp_dir("npy_replay");
+    let x = HostTensor::new(vec![2, 2], vec![1.0f32, 2.0, 3.0, 4.0]).unwrap();
+    let y = HostTensor::new(vec![2, 2], vec![10.0f32, 20.0, 30.0, 40.0]).unwrap();
+    npy::save(dir.join("x.npy"), &x).unwrap();
+    npy::save(dir.join("y.npy"), &y).unwrap();
+
+    let mut predictor = config(ADD_MODEL).build().unwrap();
+    predictor
+        .input("x")
+        .unwrap()
+        .copy_from_npy(dir.join("x.npy"))
+        .unwrap();
+    predictor
+        .input("y")
+        .unwrap()
+        .copy_from_npy(dir.join("y.npy"))
+        .unwrap();
+    predictor.run().unwrap();
+    predictor
+        .output("sum")
+        .unwrap()
+        .save_npy(dir.join("sum.npy"))
+        .unwrap();
+
+    let sum = npy::load(dir.join("sum.npy")).unwrap();
+    assert_eq!(sum.shape(), [2, 2]);
+    assert_eq!(sum.as_slice::<f32>().unwrap(), [11.0, 22.0, 33.0, 44.0]);
+    assert_eq!(predictor.output_to_host("sum").unwrap(), sum);
+
+    assert!(matches!(npy::load(dir.join("none.npy")), Err(Error::Io(_))));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_npy_data_types() {
+    let mut predictor = config("input x int64\noutput y = identity x")
+        .build()
+        .unwrap();
+    let dir = temp_dir("npy_data_types");
+    let x = HostTensor::new(vec![3], vec![i64::MIN, 0, i64::MAX]).unwrap();
+    npy::save(dir.join("x.npy"), &x).unwrap();
+
+    let mut input = predictor.input("x").unwrap();
+    input.copy_from_npy(dir.join("x.npy")).unwrap();
+    input.save_npy(dir.join("x2.npy")).unwrap();
+    assert_eq!(npy::load(dir.join("x2.npy")).unwrap(), x);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "npz")]
+#[test]
+fn test_npz() {
+    let dir = temp_dir("npz");
+    let tensors = vec![
+        (
+            "x".to_string(),
+            HostTensor::new(vec![2], vec![1.5f32, 2.5]).unwrap(),
+        ),
+        (
+            "y".to_string(),
+            HostTensor::new(vec![2], vec![1.0f32, 1.0]).unwrap(),
+        ),
+        (
+            "ids".to_string(),
+            HostTensor::new(vec![3], vec![1u8, 2, 3]).unwrap(),
+        ),
+    ];
+    let path = dir.join("inputs.npz");
+    npy::save_npz(&path, &tensors).unwrap();
+    assert_eq!(npy::load_npz(&path).unwrap(), tensors);
+
+    let mut predictor = config(ADD_MODEL).build().unwrap();
+    let outputs = predictor.run_host(&tensors[..2]).unwrap();
+    assert_eq!(outputs[0].1.as_slice::<f32>().unwrap(), [2.5, 3.5]);
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::{config, ADD_MODEL};
+use paddle_inference::{Error, HostTensor, Predictor, PredictorGuard, PredictorPool};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_send() {
+    fn send<T: Send>() {}
+    fn sync<T: Sync>() {}
+    send::<Predictor>();
+    send::<PredictorPool>();
+    sync::<PredictorPool>();
+    send::<PredictorGuard>();
+}
+
+#[test]
+fn test_acquire() {
+    let pool = PredictorPool::new(config(ADD_MODEL), 2).unwrap();
+    assert_eq!(pool.size(), 2);
+    assert_eq!(pool.idle(), 2);
+
+    let a = pool.acquire();
+    let b = pool.try_acquire().unwrap();
+    assert_eq!(pool.idle(), 0);
+    assert!(pool.try_acquire().is_none());
+
+    let start = Instant::now();
+    assert!(pool.acquire_timeout(Duration::from_millis(50)).is_none());
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    drop(a);
+    assert_eq!(pool.idle(), 1);
+    assert!(pool.acquire_timeout(Duration::from_millis(50)).is_some());
+    drop(b);
+    assert_eq!(pool.idle(), 2);
+}
+
+#[test]
+fn test_threads() {
+    let pool = PredictorPool::new(config(ADD_MODEL), 2).unwrap();
+    let handles = (0..8)
+        .map(|i| {
+            let pool = pool.clone();
+            std::thread::spawn(move || {
+                let mut predictor = pool.acquire();
+                let x = HostTensor::new(vec![1], vec![i as f32]).unwrap();
+                let y = HostTensor::new(vec![1], vec![1.0f32]).unwrap();
+                predictor.input("x").unwrap().copy_from_host(&x).unwrap();
+                predictor.input("y").unwrap().copy_from_host(&y).unwrap();
+                predictor.run().unwrap();
+                std::thread::sleep(Duration::from_millis(10));
+                let sum = predictor.output_to_host("sum").unwrap();
+                sum.into_vec::<f32>().unwrap()
+            })
+        })
+        .collect::<Vec<_>>();
+    for (i, handle) in handles.into_iter().enumerate() {
+        assert_eq!(handle.join().unwrap(), [i as f32 + 1.0]);
+    }
+    assert_eq!(pool.idle(), 2);
+}
+
+#[test]
+fn test_clone_failed() {
+    let script = format!("{}max_clones 1", ADD_MODEL);
+    assert!(PredictorPool::new(config(&script), 2).is_ok());
+    assert_eq!(
+        PredictorPool::new(config(&script), 3).err(),
+        Some(Error::NullHandle("PD_PredictorClone"))
+    );
+}
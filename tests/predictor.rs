@@ -0,0 +1,270 @@
+mod common;
+
+use common::{add_inputs, config, ADD_MODEL};
+use paddle_inference::common::DataType;
+use paddle_inference::{Error, HostTensor, ShrinkPolicy};
+use std::time::Duration;
+
+#[test]
+fn test_run() {
+    let mut predictor = config(ADD_MODEL).build().unwrap();
+
+    let mut x = predictor.input("x").unwrap();
+    x.reshape(&[2, 2]);
+    x.copy_from(&[1.0f32, 2.0, 3.0, 4.0]).unwrap();
+    drop(x);
+    let mut y = predictor.input("y").unwrap();
+    y.reshape(&[2, 2]);
+    y.copy_from(&[10.0f32, 20.0, 30.0, 40.0]).unwrap();
+    drop(y);
+    predictor.run().unwrap();
+
+    let sum = predictor.output("sum").unwrap();
+    assert_eq!(sum.shape(), [2, 2]);
+    let mut data = vec![0.0f32; 4];
+    sum.copy_to(&mut data).unwrap();
+    assert_eq!(data, [11.0, 22.0, 33.0, 44.0]);
+    drop(sum);
+
+    let copy = predictor.output("copy").unwrap();
+    copy.copy_to(&mut data).unwrap();
+    assert_eq!(data, [1.0, 2.0, 3.0, 4.0]);
+}
+
+#[test]
+fn test_run_failed() {
+    let mut predictor = config(ADD_MODEL).build().unwrap();
+    assert_eq!(predictor.run(), Err(Error::RunFailed));
+
+    let mut x = predictor.input("x").unwrap();
+    x.reshape(&[1, 2]);
+    x.copy_from(&[1.0f32, 2.0]).unwrap();
+    drop(x);
+    let mut y = predictor.input("y").unwrap();
+    y.reshape(&[2, 1]);
+    y.copy_from(&[1.0f32, 2.0]).unwrap();
+    drop(y);
+    assert_eq!(predictor.run(), Err(Error::RunFailed));
+}
+
+#[test]
+fn test_missing_tensor() {
+    let mut predictor = config(ADD_MODEL).build().unwrap();
+    assert!(matches!(
+        predictor.input("z"),
+        Err(Error::NullHandle("PD_PredictorGetInputHandle"))
+    ));
+    assert!(matches!(
+        predictor.output("x"),
+        Err(Error::NullHandle("PD_PredictorGetOutputHandle"))
+    ));
+}
+
+#[test]
+fn test_clone() {
+    let mut predictor = config("input x int64\noutput y = identity x")
+        .build()
+        .unwrap();
+    let mut clone = predictor.clone();
+
+    let mut x = predictor.input("x").unwrap();
+    x.reshape(&[3]);
+    x.copy_from(&[1i64, 2, 3]).unwrap();
+    drop(x);
+    predictor.run().unwrap();
+
+    // 克隆的 Predictor 拥有独立的输入输出
+    assert_eq!(clone.run(), Err(Error::RunFailed));
+    let mut x = clone.input("x").unwrap();
+    x.reshape(&[1]);
+    x.copy_from(&[7i64]).unwrap();
+    drop(x);
+    clone.run().unwrap();
+
+    let mut data = [0i64; 3];
+    predictor.output("y").unwrap().copy_to(&mut data).unwrap();
+    assert_eq!(data, [1, 2, 3]);
+    let mut data = [0i64; 1];
+    clone.output("y").unwrap().copy_to(&mut data).unwrap();
+    assert_eq!(data, [7]);
+}
+
+#[test]
+fn test_set_input() {
+    let mut predictor = config(ADD_MODEL).build().unwrap();
+    predictor
+        .set_input("x", &[1, 3], &[1.0f32, 2.0, 3.0])
+        .unwrap();
+    let x = HostTensor::new(vec![3], vec![1.0f32, 2.0, 3.0]).unwrap();
+    let y = HostTensor::new(vec![3], vec![4.0f32, 5.0, 6.0]).unwrap();
+    predictor.set_inputs([("x", &x), ("y", &y)]).unwrap();
+    predictor.run().unwrap();
+
+    let mut data = [0.0f32; 3];
+    predictor.output("sum").unwrap().copy_to(&mut data).unwrap();
+    assert_eq!(data, [5.0, 7.0, 9.0]);
+
+    assert_eq!(
+        predictor.set_input("z", &[1], &[1.0f32]),
+        Err(Error::TensorNotFound("z".to_string()))
+    );
+    assert_eq!(
+        predictor.set_input("x", &[2, 2], &[1.0f32, 2.0, 3.0]),
+        Err(Error::ShapeMismatch {
+            shape: vec![2, 2],
+            len: 3
+        })
+    );
+    assert!(matches!(
+        predictor.set_input("x", &[-1], &[1.0f32]),
+        Err(Error::ShapeMismatch { .. })
+    ));
+    assert_eq!(
+        predictor.set_input("x", &[1], &[1i64]),
+        Err(Error::DataTypeMismatch {
+            expected: DataType::Int64,
+            actual: DataType::Float32
+        })
+    );
+
+    // 检查失败时不会写入任何数据
+    let one = HostTensor::new(vec![1], vec![0.0f32]).unwrap();
+    assert!(predictor.set_inputs([("x", &one), ("z", &one)]).is_err());
+    assert_eq!(predictor.input("x").unwrap().shape(), [3]);
+}
+
+#[test]
+fn test_set_inputs_mixed_types() {
+    let mut predictor = config(
+        "
+input ids int64
+input mask float32
+output ids_out = identity ids
+output mask_out = identity mask
+",
+    )
+    .build()
+    .unwrap();
+    let ids = HostTensor::new(vec![1, 3], vec![1i64, 2, 3]).unwrap();
+    let mask = HostTensor::new(vec![1, 3], vec![1.0f32, 1.0, 0.0]).unwrap();
+    predictor
+        .set_inputs([("ids", &ids), ("mask", &mask)])
+        .unwrap();
+    predictor.run().unwrap();
+    assert_eq!(predictor.output_to_host("ids_out").unwrap(), ids);
+    assert_eq!(predictor.output_to_host("mask_out").unwrap(), mask);
+
+    assert_eq!(
+        predictor.set_inputs([("ids", &mask), ("mask", &ids)]),
+        Err(Error::DataTypeMismatch {
+            expected: DataType::Float32,
+            actual: DataType::Int64
+        })
+    );
+}
+
+#[test]
+fn test_run_host_checks_inputs() {
+    let mut predictor = config("input x int64\noutput y = identity x")
+        .build()
+        .unwrap();
+    let x = HostTensor::new(vec![2], vec![1i64, 2]).unwrap();
+    let outputs = predictor.run_host(&[("x".to_string(), x.clone())]).unwrap();
+    assert_eq!(outputs, [("y".to_string(), x.clone())]);
+
+    let float = HostTensor::new(vec![2], vec![1.0f32, 2.0]).unwrap();
+    assert_eq!(
+        predictor.run_host(&[("x".to_string(), float)]),
+        Err(Error::DataTypeMismatch {
+            expected: DataType::Float32,
+            actual: DataType::Int64
+        })
+    );
+    assert_eq!(
+        predictor.run_host(&[("z".to_string(), x.clone())]),
+        Err(Error::TensorNotFound("z".to_string()))
+    );
+    // 检查失败时不写入数据
+    assert_eq!(predictor.output_to_host("y").unwrap(), x);
+}
+
+#[test]
+fn test_host_tensor() {
+    let mut predictor = config(ADD_MODEL).build().unwrap();
+    assert_eq!(
+        predictor.output_to_host("sum"),
+        Err(Error::UnknownDataType("sum".to_string()))
+    );
+
+    let x = HostTensor::new(vec![2], vec![1.0f32, 2.0]).unwrap();
+    let y = HostTensor::new(vec![2], vec![10.0f32, 20.0]).unwrap();
+    predictor.input("x").unwrap().copy_from_host(&x).unwrap();
+    predictor.input("y").unwrap().copy_from_host(&y).unwrap();
+    predictor.run().unwrap();
+
+    let sum = predictor.output_to_host("sum").unwrap();
+    assert_eq!(sum, HostTensor::new(vec![2], vec![11.0f32, 22.0]).unwrap());
+    assert_eq!(
+        predictor.output_to_host("x"),
+        Err(Error::TensorNotFound("x".to_string()))
+    );
+
+    let outputs = predictor.outputs_to_host().unwrap();
+    assert_eq!(
+        outputs,
+        [("sum".to_string(), sum), ("copy".to_string(), x.clone())]
+    );
+    // 复制后的数据与 Predictor 无关
+    drop(predictor);
+    assert_eq!(outputs[1].1.as_slice::<f32>(), Some(&[1.0, 2.0][..]));
+}
+
+#[test]
+fn test_shrink_memory() {
+    let mut predictor = config(ADD_MODEL).build().unwrap();
+    predictor.clear_intermediate_tensors();
+    assert_eq!(predictor.try_shrink_memory(), 0);
+
+    // 两个输入及两个输出，每个 4 个 float32
+    predictor.run_host(&add_inputs(&[4], 1.0)).unwrap();
+    assert_eq!(predictor.try_shrink_memory(), 64);
+    assert_eq!(predictor.try_shrink_memory(), 0);
+}
+
+#[test]
+fn test_shrink_policy() {
+    let mut predictor = config(ADD_MODEL).build().unwrap();
+    predictor.set_shrink_policy(ShrinkPolicy {
+        input_bytes: Some(32),
+        idle: Some(Duration::from_millis(50)),
+    });
+    assert_eq!(predictor.shrink_if_idle(), None);
+
+    // 输入共 32 字节，未超过阈值
+    predictor.run_host(&add_inputs(&[4], 1.0)).unwrap();
+    assert_eq!(predictor.shrink_if_idle(), None);
+    std::thread::sleep(Duration::from_millis(60));
+    assert_eq!(predictor.shrink_if_idle(), Some(64));
+    assert_eq!(predictor.shrink_if_idle(), None);
+
+    // 输入共 64 字节，预测后自动释放
+    predictor.run_host(&add_inputs(&[8], 1.0)).unwrap();
+    assert_eq!(predictor.try_shrink_memory(), 0);
+
+    let mut clone = predictor.clone();
+    assert_eq!(clone.shrink_policy(), predictor.shrink_policy());
+    clone.run_host(&add_inputs(&[8], 1.0)).unwrap();
+    assert_eq!(clone.try_shrink_memory(), 0);
+}
+
+#[test]
+fn test_try_clone() {
+    let predictor = config("input x int64\noutput y = identity x\nmax_clones 1")
+        .build()
+        .unwrap();
+    let clone = predictor.try_clone().unwrap();
+    assert_eq!(
+        clone.try_clone().err(),
+        Some(Error::NullHandle("PD_PredictorClone"))
+    );
+}
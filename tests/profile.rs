@@ -0,0 +1,41 @@
+mod common;
+
+use common::{config, ADD_MODEL};
+use paddle_inference::{HostTensor, ProfileReport, ProfileSortKey};
+
+#[test]
+fn test_profile() {
+    let mut predictor = config(ADD_MODEL).enable_profile().build().unwrap();
+    let report = predictor.config_report();
+    assert_eq!(report.profile, Some(true));
+    assert!(report
+        .summary
+        .as_deref()
+        .unwrap()
+        .contains("PD_ConfigEnableProfile"));
+
+    let x = HostTensor::new(vec![2], vec![1.0f32, 2.0]).unwrap();
+    let inputs = vec![("x".to_string(), x.clone()), ("y".to_string(), x)];
+    for _ in 0..3 {
+        predictor.run_host(&inputs).unwrap();
+    }
+
+    let ((), mut report) = ProfileReport::capture(|| drop(predictor)).unwrap();
+    assert_eq!(report.ops.len(), 2);
+    report.sort_by(ProfileSortKey::Calls);
+    for op in &report.ops {
+        assert_eq!(op.calls, 3);
+        assert!(op.min <= op.avg && op.avg <= op.max);
+    }
+    assert!(report.get("elementwise_add").is_some());
+    assert!(report.get("assign").is_some());
+    assert!(report.get("elementwise_add/compute").is_none());
+    assert!((report.ops.iter().map(|op| op.ratio).sum::<f64>() - 1.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_profile_disabled() {
+    let predictor = config(ADD_MODEL).build().unwrap();
+    assert_eq!(predictor.config_report().profile, Some(false));
+    assert!(ProfileReport::capture(|| drop(predictor)).is_err());
+}
@@ -0,0 +1,180 @@
+mod common;
+
+use common::config;
+use paddle_inference::common::{DataType, PlaceType};
+use paddle_inference::{Element, Error, Lod};
+
+#[test]
+fn test_tensor_info() {
+    let mut predictor = config("input x int32\noutput y = identity x")
+        .build()
+        .unwrap();
+    let mut x = predictor.input("x").unwrap();
+    assert_eq!(x.name(), "x");
+    assert_eq!(x.data_type(), DataType::Int32);
+
+    x.reshape(&[2, 3]);
+    assert_eq!(x.shape(), [2, 3]);
+    drop(x);
+    // 同名的 Tensor 共享数据
+    assert_eq!(predictor.input("x").unwrap().shape(), [2, 3]);
+}
+
+#[test]
+fn test_copy_check() {
+    let mut predictor = config("input x uint8\noutput y = identity x")
+        .build()
+        .unwrap();
+    let mut x = predictor.input("x").unwrap();
+    x.reshape(&[2, 2]);
+    assert_eq!(
+        x.copy_from(&[1u8, 2, 3]),
+        Err(Error::ShapeMismatch {
+            shape: vec![2, 2],
+            len: 3
+        })
+    );
+    x.copy_from(&[1u8, 2, 3, 4]).unwrap();
+
+    let mut data = [0.0f32; 4];
+    assert_eq!(
+        x.copy_to(&mut data),
+        Err(Error::DataTypeMismatch {
+            expected: DataType::Float32,
+            actual: DataType::Uint8
+        })
+    );
+    let mut data = [0u8; 3];
+    assert!(matches!(
+        x.copy_to(&mut data),
+        Err(Error::ShapeMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_invalid_shape() {
+    let mut predictor = config("input x float32\noutput y = identity x")
+        .build()
+        .unwrap();
+    let mut x = predictor.input("x").unwrap();
+    // 尚未推导的维度为 -1
+    x.reshape(&[-1, 3]);
+    assert_eq!(x.to_vec::<f32>(), Err(Error::InvalidShape(vec![-1, 3])));
+    assert_eq!(x.to_host(), Err(Error::InvalidShape(vec![-1, 3])));
+    assert_eq!(
+        x.copy_from(&[1.0f32; 3]),
+        Err(Error::InvalidShape(vec![-1, 3]))
+    );
+
+    let shape = [i32::MAX, i32::MAX, i32::MAX];
+    x.reshape(&shape);
+    assert_eq!(x.to_vec::<f32>(), Err(Error::InvalidShape(shape.to_vec())));
+}
+
+#[test]
+fn test_slice() {
+    let mut predictor = config("input x float32\noutput y = identity x")
+        .build()
+        .unwrap();
+    let mut x = predictor.input("x").unwrap();
+    x.reshape(&[3]);
+    x.as_mut_slice::<f32>(PlaceType::Cpu)
+        .unwrap()
+        .copy_from_slice(&[1.0, 2.0, 3.0]);
+    assert!(x.as_slice::<i32>().is_none());
+    drop(x);
+    predictor.run().unwrap();
+
+    let y = predictor.output("y").unwrap();
+    assert_eq!(
+        y.as_slice::<f32>(),
+        Some((PlaceType::Cpu, &[1.0, 2.0, 3.0][..]))
+    );
+}
+
+#[test]
+fn test_generic_element() {
+    fn round_trip<T: Element + std::fmt::Debug + PartialEq>(data_type: &str, data: &[T]) {
+        let script = format!("input x {}\noutput y = identity x", data_type);
+        let mut predictor = config(&script).build().unwrap();
+        let mut x = predictor.input("x").unwrap();
+        assert_eq!(x.data_type(), T::DATA_TYPE);
+        x.reshape(&[data.len() as i32]);
+        x.copy_from(data).unwrap();
+        drop(x);
+        predictor.run().unwrap();
+
+        let y = predictor.output("y").unwrap();
+        assert_eq!(y.data_type(), T::DATA_TYPE);
+        assert_eq!(y.to_vec::<T>().unwrap(), data);
+        assert_eq!(y.as_slice::<T>().map(|(_, s)| s), Some(data));
+    }
+
+    round_trip("float32", &[1.5f32, -2.0]);
+    round_trip("int64", &[i64::MAX, -1]);
+    round_trip("int32", &[7i32, 8, 9]);
+    round_trip("uint8", &[0u8, 255]);
+    round_trip("int8", &[-128i8, 127]);
+}
+
+#[cfg(feature = "ndarray")]
+#[test]
+fn test_ndarray() {
+    use common::ADD_MODEL;
+    use ndarray::{arr2, ArrayD, IxDyn};
+
+    let mut predictor = config(ADD_MODEL).build().unwrap();
+    let x = arr2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    // 转置后的视图不是标准内存布局
+    let y = arr2(&[[10.0f32, 40.0], [20.0, 50.0], [30.0, 60.0]]);
+    predictor
+        .input("x")
+        .unwrap()
+        .copy_from_array(&x.view())
+        .unwrap();
+    predictor
+        .input("y")
+        .unwrap()
+        .copy_from_array(&y.t())
+        .unwrap();
+    predictor.run().unwrap();
+
+    let sum = predictor.output("sum").unwrap();
+    let expected =
+        ArrayD::from_shape_vec(IxDyn(&[2, 3]), vec![11.0f32, 22.0, 33.0, 44.0, 55.0, 66.0])
+            .unwrap();
+    assert_eq!(sum.to_array::<f32>().unwrap(), expected);
+    assert_eq!(sum.view::<f32>().unwrap(), expected.view());
+    assert!(sum.view::<i32>().is_none());
+    assert!(sum.to_array::<i64>().is_err());
+}
+
+#[test]
+fn test_lod() {
+    let mut predictor = config("input x int64\noutput y = identity x")
+        .build()
+        .unwrap();
+    let mut x = predictor.input("x").unwrap();
+    x.copy_from_sequences(&[vec![1i64, 2], vec![3, 4, 5], vec![]])
+        .unwrap();
+    assert_eq!(x.shape(), [5, 1]);
+    assert_eq!(x.lod().levels(), [vec![0, 2, 5, 5]]);
+
+    let lod = Lod::new(vec![vec![0, 1, 5]]).unwrap();
+    x.set_lod(&lod).unwrap();
+    assert!(matches!(
+        x.set_lod(&Lod::new(vec![vec![0, 4]]).unwrap()),
+        Err(Error::InvalidLod(_))
+    ));
+    drop(x);
+
+    predictor.run().unwrap();
+    let mut y = predictor.output("y").unwrap();
+    assert_eq!(y.lod(), lod);
+    assert_eq!(y.to_vec::<i64>().unwrap(), [1, 2, 3, 4, 5]);
+    assert_eq!(y.lod().sequences().collect::<Vec<_>>(), [0..1, 1..5]);
+
+    // 空 LoD 用于清除 LoD 信息
+    y.set_lod(&Lod::default()).unwrap();
+    assert!(y.lod().is_empty());
+}
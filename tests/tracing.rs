@@ -0,0 +1,119 @@
+#![cfg(feature = "tracing")]
+
+mod common;
+
+use common::{add_inputs, config, ADD_MODEL};
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// 记录所有 span 的名称、字段及父 span 名称
+#[derive(Default)]
+struct Recorder {
+    next_id: AtomicU64,
+    spans: Arc<Mutex<Vec<Span>>>,
+    stack: Mutex<Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+struct Span {
+    name: String,
+    fields: String,
+    parent: Option<String>,
+}
+
+struct Fields<'a>(&'a mut String);
+
+impl Visit for Fields<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.push_str(&format!("{}={:?} ", field.name(), value));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = String::new();
+        span.record(&mut Fields(&mut fields));
+        self.spans.lock().unwrap().push(Span {
+            name: span.metadata().name().to_string(),
+            fields,
+            parent: None,
+        });
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, id: &Id) {
+        let mut spans = self.spans.lock().unwrap();
+        let mut stack = self.stack.lock().unwrap();
+        let span = &mut spans[id.into_u64() as usize - 1];
+        span.parent = stack.last().cloned();
+        stack.push(span.name.clone());
+    }
+
+    fn exit(&self, _: &Id) {
+        self.stack.lock().unwrap().pop();
+    }
+}
+
+#[test]
+fn test_spans() {
+    let recorder = Recorder::default();
+    let spans = recorder.spans.clone();
+    tracing::subscriber::with_default(recorder, || {
+        let mut predictor = config(ADD_MODEL).model_name("traced").build().unwrap();
+        predictor.run_host(&add_inputs(&[2], 1.0)).unwrap();
+    });
+    let spans = spans.lock().unwrap();
+    let find = |name: &str| {
+        spans
+            .iter()
+            .filter(|s| s.name == name)
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    let build = &find("Config::build")[0];
+    assert!(
+        build.fields.contains(r#"model_name="traced""#),
+        "{:?}",
+        build
+    );
+    assert!(build.fields.contains(r#"device="cpu""#), "{:?}", build);
+    assert!(build.fields.contains("passes=Passes"), "{:?}", build);
+
+    let reshape = find("Tensor::reshape");
+    assert_eq!(reshape.len(), 2);
+    assert!(
+        reshape[0].fields.contains("name=x shape=[2]"),
+        "{:?}",
+        reshape
+    );
+
+    let copy_from = find("Tensor::copy_from");
+    assert_eq!(copy_from.len(), 2);
+    assert!(
+        copy_from[1].fields.contains("data_type=Float32"),
+        "{:?}",
+        copy_from
+    );
+    assert_eq!(copy_from[1].parent.as_deref(), Some("Predictor::run_host"));
+
+    let run = &find("Predictor::run")[0];
+    assert_eq!(run.parent.as_deref(), Some("Predictor::run_host"));
+    assert!(run.fields.contains(r#"model_name="traced""#), "{:?}", run);
+    // 两个输出
+    assert_eq!(find("Tensor::copy_to").len(), 2);
+}